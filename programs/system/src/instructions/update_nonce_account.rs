@@ -29,7 +29,7 @@ impl UpdateNonceAccount<'_> {
         let instruction = Instruction {
             program_id: &crate::ID,
             accounts: &account_metas,
-            data: &[12],
+            data: &[12, 0, 0, 0],
         };
 
         invoke_signed(&instruction, &[self.account], signers)
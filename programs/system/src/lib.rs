@@ -2,4 +2,7 @@
 
 pub mod instructions;
 
+#[cfg(all(not(target_os = "solana"), feature = "testing"))]
+pub mod testing;
+
 pinocchio_pubkey::declare_id!("11111111111111111111111111111111");
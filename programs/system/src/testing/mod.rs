@@ -0,0 +1,751 @@
+//! Host-side emulator of the System program.
+//!
+//! The emulator applies System instructions to the account memory of the
+//! `pinocchio` in-process runtime, so the CPI helpers of this crate move
+//! lamports, allocate data and assign owners in host tests. It is registered
+//! with [`register`], after which the helpers can be invoked from programs
+//! processed by [`pinocchio::testing::process_instruction`]:
+//!
+//! ```
+//! use pinocchio::{
+//!     account_info::AccountInfo,
+//!     entrypoint::serialize::{InputAccount, InputBuilder},
+//!     pubkey::Pubkey,
+//!     testing::{self, InputAccounts},
+//!     ProgramResult,
+//! };
+//! use pinocchio_system::instructions::Transfer;
+//!
+//! const PROGRAM: Pubkey = [1; 32];
+//!
+//! fn process(_: &Pubkey, accounts: &[AccountInfo], _: &[u8]) -> ProgramResult {
+//!     Transfer { from: &accounts[0], to: &accounts[1], lamports: 5 }.invoke()
+//! }
+//!
+//! pinocchio_system::testing::register();
+//! testing::register_program(PROGRAM, process);
+//!
+//! let accounts = InputAccounts::new(
+//!     InputBuilder::new(PROGRAM)
+//!         .account(InputAccount::new([2; 32], 10, pinocchio_system::ID).signer().writable())
+//!         .account(InputAccount::new([3; 32], 0, pinocchio_system::ID).writable())
+//!         .build(),
+//! );
+//!
+//! testing::process_instruction(&PROGRAM, &accounts, &[]).unwrap();
+//!
+//! assert_eq!(accounts[1].lamports(), 5);
+//! ```
+//!
+//! Instructions fail with the same errors as the System program, where the
+//! [`SystemError`] variants are returned as [`ProgramError::Custom`] codes.
+//! Runtime errors without a [`ProgramError`] equivalent, raised when debiting
+//! or reassigning an account not owned by the System program, are returned
+//! as [`ProgramError::InvalidAccountOwner`].
+//!
+//! Account data can only grow by [`MAX_PERMITTED_DATA_INCREASE`] bytes on
+//! the host, since that is the space reserved after each account in the
+//! serialized input.
+//!
+//! [`MAX_PERMITTED_DATA_INCREASE`]: pinocchio::account_info::MAX_PERMITTED_DATA_INCREASE
+
+mod nonce;
+
+pub use nonce::{durable_nonce, NonceState, NONCE_STATE_LEN, RECENT_BLOCKHASHES_ID};
+
+use pinocchio::{
+    account_info::AccountInfo,
+    hash::sha256,
+    program_error::ProgramError,
+    pubkey::{Pubkey, MAX_SEED_LEN, PDA_MARKER},
+    testing::InstructionData,
+    ProgramResult,
+};
+
+/// Maximum data length of an account, in bytes.
+pub const MAX_PERMITTED_DATA_LENGTH: u64 = 10 * 1024 * 1024;
+
+/// Errors returned by the System program.
+#[repr(u32)]
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+pub enum SystemError {
+    /// An account with the same address already exists.
+    AccountAlreadyInUse,
+
+    /// Account does not have enough lamports to perform the operation.
+    ResultWithNegativeLamports,
+
+    /// Cannot assign account to this program id.
+    InvalidProgramId,
+
+    /// Cannot allocate account data of this length.
+    InvalidAccountDataLength,
+
+    /// Length of requested seed is too long.
+    MaxSeedLengthExceeded,
+
+    /// Provided address does not match addressed derived from seed.
+    AddressWithSeedMismatch,
+
+    /// Advancing stored nonce requires a populated recent blockhashes sysvar.
+    NonceNoRecentBlockhashes,
+
+    /// Stored nonce is still in recent blockhashes.
+    NonceBlockhashNotExpired,
+
+    /// Specified nonce does not match stored nonce.
+    NonceUnexpectedBlockhashValue,
+}
+
+impl From<SystemError> for ProgramError {
+    fn from(error: SystemError) -> Self {
+        ProgramError::Custom(error as u32)
+    }
+}
+
+/// Register the emulator as the System program of the current thread.
+#[inline]
+pub fn register() {
+    pinocchio::testing::register_program(crate::ID, process_instruction);
+}
+
+/// Process a System instruction.
+pub fn process_instruction(
+    _program_id: &Pubkey,
+    accounts: &[AccountInfo],
+    instruction_data: &[u8],
+) -> ProgramResult {
+    let mut data = InstructionData::new(instruction_data, ProgramError::InvalidInstructionData);
+
+    match data.u32()? {
+        // CreateAccount
+        0 => {
+            let lamports = data.u64()?;
+            let space = data.u64()?;
+            let owner = data.pubkey()?;
+            let [from, to, ..] = accounts else {
+                return Err(ProgramError::NotEnoughAccountKeys);
+            };
+
+            create_account(accounts, from, to, None, lamports, space, &owner)
+        }
+        // Assign
+        1 => {
+            let owner = data.pubkey()?;
+            let [account, ..] = accounts else {
+                return Err(ProgramError::NotEnoughAccountKeys);
+            };
+
+            assign(accounts, account, None, &owner)
+        }
+        // Transfer
+        2 => {
+            let lamports = data.u64()?;
+            let [from, to, ..] = accounts else {
+                return Err(ProgramError::NotEnoughAccountKeys);
+            };
+
+            if !from.is_signer() {
+                return Err(ProgramError::MissingRequiredSignature);
+            }
+            transfer(from, to, lamports)
+        }
+        // CreateAccountWithSeed
+        3 => {
+            let base = data.pubkey()?;
+            let seed = seed(&mut data)?;
+            let lamports = data.u64()?;
+            let space = data.u64()?;
+            let owner = data.pubkey()?;
+            let [from, to, ..] = accounts else {
+                return Err(ProgramError::NotEnoughAccountKeys);
+            };
+
+            create_account(
+                accounts,
+                from,
+                to,
+                Some((&base, seed)),
+                lamports,
+                space,
+                &owner,
+            )
+        }
+        // AdvanceNonceAccount
+        4 => nonce::advance(accounts),
+        // WithdrawNonceAccount
+        5 => nonce::withdraw(accounts, data.u64()?),
+        // InitializeNonceAccount
+        6 => nonce::initialize(accounts, &data.pubkey()?),
+        // AuthorizeNonceAccount
+        7 => nonce::authorize(accounts, &data.pubkey()?),
+        // Allocate
+        8 => {
+            let space = data.u64()?;
+            let [account, ..] = accounts else {
+                return Err(ProgramError::NotEnoughAccountKeys);
+            };
+
+            allocate(accounts, account, None, space)
+        }
+        // AllocateWithSeed
+        9 => {
+            let base = data.pubkey()?;
+            let seed = seed(&mut data)?;
+            let space = data.u64()?;
+            let owner = data.pubkey()?;
+            let [account, _base, ..] = accounts else {
+                return Err(ProgramError::NotEnoughAccountKeys);
+            };
+
+            verify_address(account.key(), &base, seed, &owner)?;
+            allocate(accounts, account, Some(&base), space)?;
+            assign(accounts, account, Some(&base), &owner)
+        }
+        // AssignWithSeed
+        10 => {
+            let base = data.pubkey()?;
+            let seed = seed(&mut data)?;
+            let owner = data.pubkey()?;
+            let [account, _base, ..] = accounts else {
+                return Err(ProgramError::NotEnoughAccountKeys);
+            };
+
+            verify_address(account.key(), &base, seed, &owner)?;
+            assign(accounts, account, Some(&base), &owner)
+        }
+        // TransferWithSeed
+        11 => {
+            let lamports = data.u64()?;
+            let seed = seed(&mut data)?;
+            let from_owner = data.pubkey()?;
+            let [from, base, to, ..] = accounts else {
+                return Err(ProgramError::NotEnoughAccountKeys);
+            };
+
+            if !base.is_signer() {
+                return Err(ProgramError::MissingRequiredSignature);
+            }
+            if *from.key() != create_with_seed(base.key(), seed, &from_owner)? {
+                return Err(SystemError::AddressWithSeedMismatch.into());
+            }
+            transfer(from, to, lamports)
+        }
+        // UpgradeNonceAccount
+        12 => nonce::upgrade(accounts),
+        _ => Err(ProgramError::InvalidInstructionData),
+    }
+}
+
+/// Derive the address of an account from a base address, a seed and the
+/// owner program.
+pub fn create_with_seed(base: &Pubkey, seed: &str, owner: &Pubkey) -> Result<Pubkey, ProgramError> {
+    if seed.len() > MAX_SEED_LEN {
+        return Err(ProgramError::MaxSeedLengthExceeded);
+    }
+    if owner.ends_with(PDA_MARKER) {
+        return Err(ProgramError::IllegalOwner);
+    }
+
+    Ok(sha256(&[base, seed.as_bytes(), owner]))
+}
+
+/// Return whether an account with the key `address` signed the instruction.
+fn is_signer(accounts: &[AccountInfo], address: &Pubkey) -> bool {
+    accounts
+        .iter()
+        .any(|account| account.is_signer() && account.key() == address)
+}
+
+/// Verify that `address` is derived from `base`, `seed` and `owner`.
+fn verify_address(address: &Pubkey, base: &Pubkey, seed: &str, owner: &Pubkey) -> ProgramResult {
+    if *address != create_with_seed(base, seed, owner)? {
+        return Err(SystemError::AddressWithSeedMismatch.into());
+    }
+    Ok(())
+}
+
+/// Create `to`, funded by `from`.
+///
+/// The signer of `to` is `base` when the address is derived with a seed.
+fn create_account(
+    accounts: &[AccountInfo],
+    from: &AccountInfo,
+    to: &AccountInfo,
+    with_seed: Option<(&Pubkey, &str)>,
+    lamports: u64,
+    space: u64,
+    owner: &Pubkey,
+) -> ProgramResult {
+    let base = match with_seed {
+        Some((base, seed)) => {
+            verify_address(to.key(), base, seed, owner)?;
+            Some(base)
+        }
+        None => None,
+    };
+
+    if to.lamports() > 0 {
+        return Err(SystemError::AccountAlreadyInUse.into());
+    }
+    allocate(accounts, to, base, space)?;
+    assign(accounts, to, base, owner)?;
+
+    if !from.is_signer() {
+        return Err(ProgramError::MissingRequiredSignature);
+    }
+    transfer(from, to, lamports)
+}
+
+/// Allocate `space` bytes of data for `account`.
+fn allocate(
+    accounts: &[AccountInfo],
+    account: &AccountInfo,
+    base: Option<&Pubkey>,
+    space: u64,
+) -> ProgramResult {
+    if !is_signer(accounts, base.unwrap_or(account.key())) {
+        return Err(ProgramError::MissingRequiredSignature);
+    }
+    if !account.data_is_empty() || !account.is_owned_by(&crate::ID) {
+        return Err(SystemError::AccountAlreadyInUse.into());
+    }
+    if space > MAX_PERMITTED_DATA_LENGTH {
+        return Err(SystemError::InvalidAccountDataLength.into());
+    }
+
+    account.realloc(space as usize, true)
+}
+
+/// Assign `account` to `owner`.
+///
+/// The runtime only lets the System program reassign accounts it owns, with
+/// zeroed data, and fails with `ModifiedProgramId` otherwise; the emulator
+/// returns [`ProgramError::InvalidAccountOwner`].
+fn assign(
+    accounts: &[AccountInfo],
+    account: &AccountInfo,
+    base: Option<&Pubkey>,
+    owner: &Pubkey,
+) -> ProgramResult {
+    if account.is_owned_by(owner) {
+        return Ok(());
+    }
+    if !is_signer(accounts, base.unwrap_or(account.key())) {
+        return Err(ProgramError::MissingRequiredSignature);
+    }
+    if !account.is_owned_by(&crate::ID) || account.try_borrow_data()?.iter().any(|byte| *byte != 0)
+    {
+        return Err(ProgramError::InvalidAccountOwner);
+    }
+
+    // SAFETY: No reference to the owner is held by the emulator.
+    unsafe { account.assign(owner) };
+    Ok(())
+}
+
+/// Move `lamports` from `from` to `to`.
+fn transfer(from: &AccountInfo, to: &AccountInfo, lamports: u64) -> ProgramResult {
+    if !from.data_is_empty() {
+        return Err(ProgramError::InvalidArgument);
+    }
+    if lamports > from.lamports() {
+        return Err(SystemError::ResultWithNegativeLamports.into());
+    }
+
+    move_lamports(from, to, lamports)
+}
+
+/// Move `lamports` from `from` to `to`, which may be the same account.
+///
+/// The runtime only lets the System program debit accounts it owns, and fails
+/// with `ExternalAccountLamportSpend` otherwise; the emulator returns
+/// [`ProgramError::InvalidAccountOwner`].
+fn move_lamports(from: &AccountInfo, to: &AccountInfo, lamports: u64) -> ProgramResult {
+    if lamports > 0 && !from.is_owned_by(&crate::ID) {
+        return Err(ProgramError::InvalidAccountOwner);
+    }
+
+    {
+        let mut from_lamports = from.try_borrow_mut_lamports()?;
+        *from_lamports = from_lamports
+            .checked_sub(lamports)
+            .ok_or(ProgramError::InsufficientFunds)?;
+    }
+
+    let mut to_lamports = to.try_borrow_mut_lamports()?;
+    *to_lamports = to_lamports
+        .checked_add(lamports)
+        .ok_or(ProgramError::ArithmeticOverflow)?;
+
+    Ok(())
+}
+
+/// Read a bincode-encoded seed.
+fn seed<'a>(data: &mut InstructionData<'a>) -> Result<&'a str, ProgramError> {
+    let len = usize::try_from(data.u64()?).map_err(|_| ProgramError::InvalidInstructionData)?;
+    core::str::from_utf8(data.slice(len)?).map_err(|_| ProgramError::InvalidInstructionData)
+}
+
+#[cfg(test)]
+mod tests {
+    extern crate std;
+
+    use pinocchio::{
+        entrypoint::serialize::{InputAccount, InputBuilder},
+        testing::{self, InputAccounts, Invoke},
+    };
+
+    use super::*;
+    use crate::instructions::{
+        AdvanceNonceAccount, Allocate, Assign, CreateAccount, CreateAccountWithSeed, Transfer,
+        TransferWithSeed, UpdateNonceAccount,
+    };
+
+    const PROGRAM: Pubkey = [1; 32];
+
+    const OWNER: Pubkey = [9; 32];
+
+    /// Process an instruction of a program that calls `invoke` with its
+    /// accounts.
+    fn process(invoke: Invoke, accounts: &[AccountInfo]) -> ProgramResult {
+        testing::reset();
+        register();
+        testing::process_invoke(&PROGRAM, invoke, accounts)
+    }
+
+    #[test]
+    fn test_create_account() {
+        fn create(accounts: &[AccountInfo]) -> ProgramResult {
+            CreateAccount {
+                from: &accounts[0],
+                to: &accounts[1],
+                lamports: 100,
+                space: 64,
+                owner: &OWNER,
+            }
+            .invoke()
+        }
+
+        let accounts = InputAccounts::new(
+            InputBuilder::new(PROGRAM)
+                .account(
+                    InputAccount::new([2; 32], 1_000, crate::ID)
+                        .signer()
+                        .writable(),
+                )
+                .account(InputAccount::new([3; 32], 0, crate::ID).signer().writable())
+                .build(),
+        );
+
+        assert_eq!(process(create, &accounts), Ok(()));
+        assert_eq!(accounts[0].lamports(), 900);
+        assert_eq!(accounts[1].lamports(), 100);
+        assert_eq!(accounts[1].data_len(), 64);
+        assert!(accounts[1].is_owned_by(&OWNER));
+
+        // The account now exists.
+        assert_eq!(
+            process(create, &accounts),
+            Err(SystemError::AccountAlreadyInUse.into())
+        );
+    }
+
+    #[test]
+    fn test_create_account_with_seed() {
+        fn create(accounts: &[AccountInfo]) -> ProgramResult {
+            CreateAccountWithSeed {
+                from: &accounts[0],
+                to: &accounts[1],
+                base: None,
+                seed: "vault",
+                lamports: 100,
+                space: 8,
+                owner: &OWNER,
+            }
+            .invoke()
+        }
+
+        let address = create_with_seed(&[2; 32], "vault", &OWNER).unwrap();
+
+        let accounts = InputAccounts::new(
+            InputBuilder::new(PROGRAM)
+                .account(
+                    InputAccount::new([2; 32], 1_000, crate::ID)
+                        .signer()
+                        .writable(),
+                )
+                .account(InputAccount::new(address, 0, crate::ID).writable())
+                .build(),
+        );
+
+        assert_eq!(process(create, &accounts), Ok(()));
+        assert_eq!(accounts[1].lamports(), 100);
+        assert_eq!(accounts[1].data_len(), 8);
+        assert!(accounts[1].is_owned_by(&OWNER));
+
+        let accounts = InputAccounts::new(
+            InputBuilder::new(PROGRAM)
+                .account(
+                    InputAccount::new([2; 32], 1_000, crate::ID)
+                        .signer()
+                        .writable(),
+                )
+                .account(InputAccount::new([3; 32], 0, crate::ID).writable())
+                .build(),
+        );
+
+        assert_eq!(
+            process(create, &accounts),
+            Err(SystemError::AddressWithSeedMismatch.into())
+        );
+    }
+
+    #[test]
+    fn test_transfer() {
+        fn transfer(accounts: &[AccountInfo]) -> ProgramResult {
+            Transfer {
+                from: &accounts[0],
+                to: &accounts[1],
+                lamports: 600,
+            }
+            .invoke()
+        }
+
+        let accounts = InputAccounts::new(
+            InputBuilder::new(PROGRAM)
+                .account(
+                    InputAccount::new([2; 32], 1_000, crate::ID)
+                        .signer()
+                        .writable(),
+                )
+                .account(InputAccount::new([3; 32], 0, crate::ID).writable())
+                .build(),
+        );
+
+        assert_eq!(process(transfer, &accounts), Ok(()));
+        assert_eq!(accounts[0].lamports(), 400);
+        assert_eq!(accounts[1].lamports(), 600);
+
+        assert_eq!(
+            process(transfer, &accounts),
+            Err(SystemError::ResultWithNegativeLamports.into())
+        );
+
+        let accounts = InputAccounts::new(
+            InputBuilder::new(PROGRAM)
+                .account(
+                    InputAccount::new([2; 32], 1_000, crate::ID)
+                        .data(&[1; 8])
+                        .signer()
+                        .writable(),
+                )
+                .account(InputAccount::new([3; 32], 0, crate::ID).writable())
+                .build(),
+        );
+
+        assert_eq!(
+            process(transfer, &accounts),
+            Err(ProgramError::InvalidArgument)
+        );
+    }
+
+    #[test]
+    fn test_transfer_with_seed() {
+        fn transfer(accounts: &[AccountInfo]) -> ProgramResult {
+            TransferWithSeed {
+                from: &accounts[0],
+                base: &accounts[1],
+                to: &accounts[2],
+                lamports: 10,
+                seed: "seed",
+                owner: &crate::ID,
+            }
+            .invoke()
+        }
+
+        let from = create_with_seed(&[2; 32], "seed", &crate::ID).unwrap();
+
+        let accounts = InputAccounts::new(
+            InputBuilder::new(PROGRAM)
+                .account(InputAccount::new(from, 100, crate::ID).writable())
+                .account(InputAccount::new([2; 32], 0, crate::ID).signer())
+                .account(InputAccount::new([3; 32], 0, crate::ID).writable())
+                .build(),
+        );
+
+        assert_eq!(process(transfer, &accounts), Ok(()));
+        assert_eq!(accounts[0].lamports(), 90);
+        assert_eq!(accounts[2].lamports(), 10);
+    }
+
+    #[test]
+    fn test_allocate_and_assign() {
+        fn allocate(accounts: &[AccountInfo]) -> ProgramResult {
+            Allocate {
+                account: &accounts[0],
+                space: 16,
+            }
+            .invoke()?;
+            Assign {
+                account: &accounts[0],
+                owner: &OWNER,
+            }
+            .invoke()
+        }
+
+        let accounts = InputAccounts::new(
+            InputBuilder::new(PROGRAM)
+                .account(InputAccount::new([2; 32], 0, crate::ID).signer().writable())
+                .build(),
+        );
+
+        assert_eq!(process(allocate, &accounts), Ok(()));
+        assert_eq!(accounts[0].data_len(), 16);
+        assert!(accounts[0].is_owned_by(&OWNER));
+
+        let accounts = InputAccounts::new(
+            InputBuilder::new(PROGRAM)
+                .account(InputAccount::new([2; 32], 0, crate::ID).writable())
+                .build(),
+        );
+
+        // The instruction requires the signature of the account.
+        assert_eq!(
+            process(allocate, &accounts),
+            Err(ProgramError::MissingRequiredSignature)
+        );
+    }
+
+    #[test]
+    fn test_transfer_from_program_account() {
+        fn transfer(accounts: &[AccountInfo]) -> ProgramResult {
+            Transfer {
+                from: &accounts[0],
+                to: &accounts[1],
+                lamports: 10,
+            }
+            .invoke()
+        }
+
+        let accounts = InputAccounts::new(
+            InputBuilder::new(PROGRAM)
+                .account(InputAccount::new([2; 32], 100, PROGRAM).signer().writable())
+                .account(InputAccount::new([3; 32], 0, crate::ID).writable())
+                .build(),
+        );
+
+        // Only accounts owned by the System program can be debited.
+        assert_eq!(
+            process(transfer, &accounts),
+            Err(ProgramError::InvalidAccountOwner)
+        );
+        assert_eq!(accounts[0].lamports(), 100);
+    }
+
+    #[test]
+    fn test_assign_program_account() {
+        fn assign(accounts: &[AccountInfo]) -> ProgramResult {
+            Assign {
+                account: &accounts[0],
+                owner: &OWNER,
+            }
+            .invoke()
+        }
+
+        let accounts = InputAccounts::new(
+            InputBuilder::new(PROGRAM)
+                .account(InputAccount::new([2; 32], 0, PROGRAM).signer().writable())
+                .account(
+                    InputAccount::new([3; 32], 0, crate::ID)
+                        .data(&[1])
+                        .signer()
+                        .writable(),
+                )
+                .build(),
+        );
+
+        // Only accounts owned by the System program can be reassigned.
+        assert_eq!(
+            process(assign, &accounts),
+            Err(ProgramError::InvalidAccountOwner)
+        );
+        assert!(accounts[0].is_owned_by(&PROGRAM));
+
+        // Their data must be zeroed.
+        assert_eq!(
+            process(assign, &accounts[1..]),
+            Err(ProgramError::InvalidAccountOwner)
+        );
+        assert!(accounts[1].is_owned_by(&crate::ID));
+    }
+
+    #[test]
+    fn test_nonce_instruction_data() {
+        fn advance(accounts: &[AccountInfo]) -> ProgramResult {
+            AdvanceNonceAccount {
+                account: &accounts[0],
+                recent_blockhashes_sysvar: &accounts[1],
+                authority: &accounts[2],
+            }
+            .invoke()
+        }
+
+        fn update(accounts: &[AccountInfo]) -> ProgramResult {
+            UpdateNonceAccount {
+                account: &accounts[0],
+            }
+            .invoke()
+        }
+
+        let mut recent_blockhashes = 1u64.to_le_bytes().to_vec();
+        recent_blockhashes.extend_from_slice(&[1; 40]);
+
+        let accounts = InputAccounts::new(
+            InputBuilder::new(PROGRAM)
+                .account(
+                    InputAccount::new([2; 32], 1_000, crate::ID)
+                        .data(&[0; NONCE_STATE_LEN])
+                        .writable(),
+                )
+                .account(
+                    InputAccount::new(RECENT_BLOCKHASHES_ID, 1, [0; 32]).data(&recent_blockhashes),
+                )
+                .account(InputAccount::new([3; 32], 0, crate::ID).signer())
+                .build(),
+        );
+
+        // The instructions are decoded, and rejected because the nonce
+        // account is not initialized.
+        assert_eq!(
+            process(advance, &accounts),
+            Err(ProgramError::InvalidAccountData)
+        );
+        assert_eq!(
+            process(update, &accounts),
+            Err(ProgramError::InvalidArgument)
+        );
+    }
+
+    #[test]
+    fn test_invalid_instruction() {
+        let accounts = InputAccounts::new(
+            InputBuilder::new(crate::ID)
+                .account(InputAccount::new([2; 32], 0, crate::ID).signer().writable())
+                .build(),
+        );
+
+        assert_eq!(
+            process_instruction(&crate::ID, &accounts, &[13, 0, 0, 0]),
+            Err(ProgramError::InvalidInstructionData)
+        );
+        assert_eq!(
+            process_instruction(&crate::ID, &accounts, &[2, 0, 0, 0, 1]),
+            Err(ProgramError::InvalidInstructionData)
+        );
+        assert_eq!(
+            process_instruction(&crate::ID, &accounts, &[2, 0, 0, 0, 1, 0, 0, 0, 0, 0, 0, 0]),
+            Err(ProgramError::NotEnoughAccountKeys)
+        );
+    }
+}
@@ -0,0 +1,466 @@
+//! Nonce account instructions of the System program emulator.
+
+use pinocchio::{
+    account_info::AccountInfo,
+    hash::{sha256, Hash},
+    program_error::ProgramError,
+    pubkey::Pubkey,
+    sysvars::rent::Rent,
+    ProgramResult,
+};
+
+use super::{is_signer, move_lamports, SystemError};
+
+/// The ID of the recent blockhashes sysvar.
+pub const RECENT_BLOCKHASHES_ID: Pubkey =
+    pinocchio_pubkey::pubkey!("SysvarRecentB1ockHashes11111111111111111111");
+
+/// Length of the data of a nonce account, in bytes.
+pub const NONCE_STATE_LEN: usize = 80;
+
+/// Prefix of the hash of a blockhash that derives a durable nonce.
+const DURABLE_NONCE_HASH_PREFIX: &[u8] = b"DURABLE_NONCE";
+
+/// Discriminator of the legacy nonce account version.
+const LEGACY_VERSION: u32 = 0;
+
+/// Discriminator of the current nonce account version.
+const CURRENT_VERSION: u32 = 1;
+
+/// State of a nonce account.
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+pub enum NonceState {
+    /// The account has not been initialized.
+    Uninitialized,
+
+    /// The account holds a durable nonce.
+    Initialized {
+        /// Address of the account allowed to use the nonce.
+        authority: Pubkey,
+
+        /// The durable nonce.
+        durable_nonce: Hash,
+
+        /// Fee per signature when the nonce was stored.
+        lamports_per_signature: u64,
+    },
+}
+
+impl NonceState {
+    /// Read the state of a nonce account from its data.
+    pub fn from_bytes(data: &[u8]) -> Result<Self, ProgramError> {
+        read(data).map(|(_, state)| state)
+    }
+}
+
+/// Return the durable nonce derived from `blockhash`.
+pub fn durable_nonce(blockhash: &Hash) -> Hash {
+    sha256(&[DURABLE_NONCE_HASH_PREFIX, blockhash])
+}
+
+/// Read the version and state of a nonce account.
+fn read(data: &[u8]) -> Result<(u32, NonceState), ProgramError> {
+    let u32_at = |offset: usize| {
+        data.get(offset..offset + 4)
+            .map(|bytes| u32::from_le_bytes(bytes.try_into().unwrap()))
+            .ok_or(ProgramError::InvalidAccountData)
+    };
+
+    let version = u32_at(0)?;
+    if version != LEGACY_VERSION && version != CURRENT_VERSION {
+        return Err(ProgramError::InvalidAccountData);
+    }
+
+    let state = match u32_at(4)? {
+        0 => NonceState::Uninitialized,
+        1 => {
+            let data = data
+                .get(8..NONCE_STATE_LEN)
+                .ok_or(ProgramError::InvalidAccountData)?;
+            NonceState::Initialized {
+                authority: data[..32].try_into().unwrap(),
+                durable_nonce: data[32..64].try_into().unwrap(),
+                lamports_per_signature: u64::from_le_bytes(data[64..].try_into().unwrap()),
+            }
+        }
+        _ => return Err(ProgramError::InvalidAccountData),
+    };
+
+    Ok((version, state))
+}
+
+/// Write the version and state of a nonce account.
+fn write(account: &AccountInfo, version: u32, state: &NonceState) -> ProgramResult {
+    let mut data = account.try_borrow_mut_data()?;
+    if data.len() < NONCE_STATE_LEN {
+        return Err(ProgramError::AccountDataTooSmall);
+    }
+
+    data[..4].copy_from_slice(&version.to_le_bytes());
+    match state {
+        NonceState::Uninitialized => data[4..8].copy_from_slice(&0u32.to_le_bytes()),
+        NonceState::Initialized {
+            authority,
+            durable_nonce,
+            lamports_per_signature,
+        } => {
+            data[4..8].copy_from_slice(&1u32.to_le_bytes());
+            data[8..40].copy_from_slice(authority);
+            data[40..72].copy_from_slice(durable_nonce);
+            data[72..80].copy_from_slice(&lamports_per_signature.to_le_bytes());
+        }
+    }
+
+    Ok(())
+}
+
+/// Read the nonce account, which must be writable.
+fn read_account(account: &AccountInfo) -> Result<(u32, NonceState), ProgramError> {
+    if !account.is_writable() {
+        return Err(ProgramError::InvalidArgument);
+    }
+    read(&account.try_borrow_data()?)
+}
+
+/// Return the most recent blockhash and its fee per signature.
+fn recent_blockhash(sysvar: &AccountInfo) -> Result<(Hash, u64), ProgramError> {
+    if sysvar.key() != &RECENT_BLOCKHASHES_ID {
+        return Err(ProgramError::InvalidArgument);
+    }
+
+    let data = sysvar.try_borrow_data()?;
+    let len = data
+        .get(..8)
+        .map(|bytes| u64::from_le_bytes(bytes.try_into().unwrap()))
+        .ok_or(ProgramError::InvalidArgument)?;
+    if len == 0 {
+        return Err(SystemError::NonceNoRecentBlockhashes.into());
+    }
+
+    let entry = data.get(8..48).ok_or(ProgramError::InvalidArgument)?;
+    Ok((
+        entry[..32].try_into().unwrap(),
+        u64::from_le_bytes(entry[32..].try_into().unwrap()),
+    ))
+}
+
+/// Process an `AdvanceNonceAccount` instruction.
+pub(super) fn advance(accounts: &[AccountInfo]) -> ProgramResult {
+    let [account, recent_blockhashes, ..] = accounts else {
+        return Err(ProgramError::NotEnoughAccountKeys);
+    };
+    let (blockhash, lamports_per_signature) = recent_blockhash(recent_blockhashes)?;
+
+    match read_account(account)? {
+        (
+            _,
+            NonceState::Initialized {
+                authority,
+                durable_nonce: current,
+                ..
+            },
+        ) => {
+            if !is_signer(accounts, &authority) {
+                return Err(ProgramError::MissingRequiredSignature);
+            }
+            let durable_nonce = durable_nonce(&blockhash);
+            if durable_nonce == current {
+                return Err(SystemError::NonceBlockhashNotExpired.into());
+            }
+
+            write(
+                account,
+                CURRENT_VERSION,
+                &NonceState::Initialized {
+                    authority,
+                    durable_nonce,
+                    lamports_per_signature,
+                },
+            )
+        }
+        (_, NonceState::Uninitialized) => Err(ProgramError::InvalidAccountData),
+    }
+}
+
+/// Process a `WithdrawNonceAccount` instruction.
+pub(super) fn withdraw(accounts: &[AccountInfo], lamports: u64) -> ProgramResult {
+    let [account, to, recent_blockhashes, rent, ..] = accounts else {
+        return Err(ProgramError::NotEnoughAccountKeys);
+    };
+    if recent_blockhashes.key() != &RECENT_BLOCKHASHES_ID {
+        return Err(ProgramError::InvalidArgument);
+    }
+    let rent = Rent::from_account_info(rent)?;
+
+    let signer = match read_account(account)? {
+        (_, NonceState::Uninitialized) => {
+            if lamports > account.lamports() {
+                return Err(ProgramError::InsufficientFunds);
+            }
+            *account.key()
+        }
+        (
+            _,
+            NonceState::Initialized {
+                authority,
+                durable_nonce: current,
+                ..
+            },
+        ) => {
+            if lamports == account.lamports() {
+                let (blockhash, _) = recent_blockhash(recent_blockhashes)?;
+                if durable_nonce(&blockhash) == current {
+                    return Err(SystemError::NonceBlockhashNotExpired.into());
+                }
+                write(account, CURRENT_VERSION, &NonceState::Uninitialized)?;
+            } else {
+                let minimum_balance = rent.minimum_balance(account.data_len());
+                let amount = lamports
+                    .checked_add(minimum_balance)
+                    .ok_or(ProgramError::InsufficientFunds)?;
+                if amount > account.lamports() {
+                    return Err(ProgramError::InsufficientFunds);
+                }
+            }
+            authority
+        }
+    };
+
+    if !is_signer(accounts, &signer) {
+        return Err(ProgramError::MissingRequiredSignature);
+    }
+
+    move_lamports(account, to, lamports)
+}
+
+/// Process an `InitializeNonceAccount` instruction.
+pub(super) fn initialize(accounts: &[AccountInfo], authority: &Pubkey) -> ProgramResult {
+    let [account, recent_blockhashes, rent, ..] = accounts else {
+        return Err(ProgramError::NotEnoughAccountKeys);
+    };
+    let (blockhash, lamports_per_signature) = recent_blockhash(recent_blockhashes)?;
+    let rent = Rent::from_account_info(rent)?;
+
+    match read_account(account)? {
+        (_, NonceState::Uninitialized) => {
+            if account.lamports() < rent.minimum_balance(account.data_len()) {
+                return Err(ProgramError::InsufficientFunds);
+            }
+
+            write(
+                account,
+                CURRENT_VERSION,
+                &NonceState::Initialized {
+                    authority: *authority,
+                    durable_nonce: durable_nonce(&blockhash),
+                    lamports_per_signature,
+                },
+            )
+        }
+        (_, NonceState::Initialized { .. }) => Err(ProgramError::InvalidAccountData),
+    }
+}
+
+/// Process an `AuthorizeNonceAccount` instruction.
+pub(super) fn authorize(accounts: &[AccountInfo], new_authority: &Pubkey) -> ProgramResult {
+    let [account, ..] = accounts else {
+        return Err(ProgramError::NotEnoughAccountKeys);
+    };
+
+    match read_account(account)? {
+        (
+            version,
+            NonceState::Initialized {
+                authority,
+                durable_nonce,
+                lamports_per_signature,
+            },
+        ) => {
+            if !is_signer(accounts, &authority) {
+                return Err(ProgramError::MissingRequiredSignature);
+            }
+
+            write(
+                account,
+                version,
+                &NonceState::Initialized {
+                    authority: *new_authority,
+                    durable_nonce,
+                    lamports_per_signature,
+                },
+            )
+        }
+        (_, NonceState::Uninitialized) => Err(ProgramError::InvalidAccountData),
+    }
+}
+
+/// Process an `UpgradeNonceAccount` instruction.
+pub(super) fn upgrade(accounts: &[AccountInfo]) -> ProgramResult {
+    let [account, ..] = accounts else {
+        return Err(ProgramError::NotEnoughAccountKeys);
+    };
+    if !account.is_owned_by(&crate::ID) {
+        return Err(ProgramError::InvalidAccountOwner);
+    }
+
+    match read_account(account)? {
+        (
+            LEGACY_VERSION,
+            NonceState::Initialized {
+                authority,
+                durable_nonce: blockhash,
+                lamports_per_signature,
+            },
+        ) => write(
+            account,
+            CURRENT_VERSION,
+            &NonceState::Initialized {
+                authority,
+                durable_nonce: durable_nonce(&blockhash),
+                lamports_per_signature,
+            },
+        ),
+        _ => Err(ProgramError::InvalidArgument),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    extern crate std;
+
+    use std::vec::Vec;
+
+    use pinocchio::{
+        entrypoint::serialize::{InputAccount, InputBuilder},
+        sysvars::rent::RENT_ID,
+        testing::InputAccounts,
+    };
+
+    use super::*;
+    use crate::testing::process_instruction;
+
+    const AUTHORITY: Pubkey = [7; 32];
+
+    /// Data of the recent blockhashes sysvar with a single `blockhash`.
+    fn recent_blockhashes(blockhash: Hash) -> Vec<u8> {
+        let mut data = 1u64.to_le_bytes().to_vec();
+        data.extend_from_slice(&blockhash);
+        data.extend_from_slice(&5_000u64.to_le_bytes());
+        data
+    }
+
+    /// Data of the rent sysvar with the default values.
+    fn rent() -> Vec<u8> {
+        let mut data = 3_480u64.to_le_bytes().to_vec();
+        data.extend_from_slice(&2f64.to_le_bytes());
+        data.push(50);
+        data
+    }
+
+    fn instruction(discriminator: u8, argument: &[u8]) -> Vec<u8> {
+        let mut data = [discriminator, 0, 0, 0].to_vec();
+        data.extend_from_slice(argument);
+        data
+    }
+
+    #[test]
+    fn test_nonce_account() {
+        let minimum_balance = 3_480 * 2 * (128 + NONCE_STATE_LEN as u64);
+
+        let accounts = InputAccounts::new(
+            InputBuilder::new(crate::ID)
+                .account(
+                    InputAccount::new([2; 32], minimum_balance + 100, crate::ID)
+                        .data(&[0; NONCE_STATE_LEN])
+                        .writable(),
+                )
+                .account(
+                    InputAccount::new(RECENT_BLOCKHASHES_ID, 1, [0; 32])
+                        .data(&recent_blockhashes([1; 32])),
+                )
+                .account(InputAccount::new(RENT_ID, 1, [0; 32]).data(&rent()))
+                .account(
+                    InputAccount::new(AUTHORITY, 0, crate::ID)
+                        .signer()
+                        .writable(),
+                )
+                .build(),
+        );
+        let [nonce, blockhashes, rent, authority] = &accounts[..] else {
+            unreachable!()
+        };
+
+        // InitializeNonceAccount
+        let initialize = [nonce.clone(), blockhashes.clone(), rent.clone()];
+        assert_eq!(
+            process_instruction(&crate::ID, &initialize, &instruction(6, &AUTHORITY)),
+            Ok(())
+        );
+        assert_eq!(
+            NonceState::from_bytes(&nonce.try_borrow_data().unwrap()),
+            Ok(NonceState::Initialized {
+                authority: AUTHORITY,
+                durable_nonce: durable_nonce(&[1; 32]),
+                lamports_per_signature: 5_000,
+            })
+        );
+        assert_eq!(
+            process_instruction(&crate::ID, &initialize, &instruction(6, &AUTHORITY)),
+            Err(ProgramError::InvalidAccountData)
+        );
+
+        // AdvanceNonceAccount
+        let advance = [nonce.clone(), blockhashes.clone(), authority.clone()];
+        assert_eq!(
+            process_instruction(&crate::ID, &advance, &instruction(4, &[])),
+            Err(SystemError::NonceBlockhashNotExpired.into())
+        );
+        blockhashes.try_borrow_mut_data().unwrap()[8..40].copy_from_slice(&[2; 32]);
+        assert_eq!(
+            process_instruction(&crate::ID, &advance, &instruction(4, &[])),
+            Ok(())
+        );
+        assert!(matches!(
+            NonceState::from_bytes(&nonce.try_borrow_data().unwrap()),
+            Ok(NonceState::Initialized { durable_nonce, .. }) if durable_nonce == super::durable_nonce(&[2; 32])
+        ));
+
+        // WithdrawNonceAccount
+        let withdraw = [
+            nonce.clone(),
+            authority.clone(),
+            blockhashes.clone(),
+            rent.clone(),
+            authority.clone(),
+        ];
+        assert_eq!(
+            process_instruction(
+                &crate::ID,
+                &withdraw,
+                &instruction(5, &101u64.to_le_bytes())
+            ),
+            Err(ProgramError::InsufficientFunds)
+        );
+        assert_eq!(
+            process_instruction(
+                &crate::ID,
+                &withdraw,
+                &instruction(5, &100u64.to_le_bytes())
+            ),
+            Ok(())
+        );
+        assert_eq!(nonce.lamports(), minimum_balance);
+        assert_eq!(authority.lamports(), 100);
+
+        // AuthorizeNonceAccount
+        let authorize = [nonce.clone(), authority.clone()];
+        assert_eq!(
+            process_instruction(&crate::ID, &authorize, &instruction(7, &[8; 32])),
+            Ok(())
+        );
+        assert_eq!(
+            process_instruction(&crate::ID, &authorize, &instruction(7, &AUTHORITY)),
+            Err(ProgramError::MissingRequiredSignature)
+        );
+    }
+}
@@ -3,6 +3,9 @@
 pub mod instructions;
 pub mod state;
 
+#[cfg(all(not(target_os = "solana"), feature = "testing"))]
+pub mod testing;
+
 pinocchio_pubkey::declare_id!("TokenkegQfeZyiNwAJbNbGKPFXCWuBvf9Ss623VQ5DA");
 
 use core::mem::MaybeUninit;
@@ -0,0 +1,1120 @@
+//! Host-side emulator of the Token program.
+//!
+//! The emulator applies Token instructions to the account memory of the
+//! `pinocchio` in-process runtime, using the [`Mint`] and [`TokenAccount`]
+//! layouts, so the CPI helpers of this crate can be tested end to end on the
+//! host. It is registered with [`register`], after which the helpers can be
+//! invoked from programs processed by [`pinocchio::testing::process_instruction`]:
+//!
+//! ```
+//! use pinocchio::{
+//!     account_info::AccountInfo,
+//!     entrypoint::serialize::{InputAccount, InputBuilder},
+//!     pubkey::Pubkey,
+//!     sysvars::{host::set_sysvar, rent::Rent},
+//!     testing::{self, InputAccounts},
+//!     ProgramResult,
+//! };
+//! use pinocchio_token::{instructions::InitializeMint2, state::Mint};
+//!
+//! const PROGRAM: Pubkey = [1; 32];
+//!
+//! fn process(_: &Pubkey, accounts: &[AccountInfo], _: &[u8]) -> ProgramResult {
+//!     InitializeMint2 {
+//!         mint: &accounts[0],
+//!         decimals: 6,
+//!         mint_authority: &[3; 32],
+//!         freeze_authority: None,
+//!     }
+//!     .invoke()
+//! }
+//!
+//! pinocchio_token::testing::register();
+//! testing::register_program(PROGRAM, process);
+//! set_sysvar(Rent::default());
+//!
+//! let lamports = Rent::default().minimum_balance(Mint::LEN);
+//! let accounts = InputAccounts::new(
+//!     InputBuilder::new(PROGRAM)
+//!         .account(
+//!             InputAccount::new([2; 32], lamports, pinocchio_token::ID)
+//!                 .data(&[0; Mint::LEN])
+//!                 .writable(),
+//!         )
+//!         .build(),
+//! );
+//!
+//! testing::process_instruction(&PROGRAM, &accounts, &[]).unwrap();
+//!
+//! assert_eq!(Mint::from_account_info(&accounts[0]).unwrap().decimals(), 6);
+//! ```
+//!
+//! Instructions fail with the same errors as the Token program, where the
+//! [`TokenError`] variants are returned as [`ProgramError::Custom`] codes.
+//! Multisig authorities and the UI amount conversion instructions are not
+//! supported; these instructions fail with [`TokenError::InvalidInstruction`].
+//!
+//! `InitializeMint2` and `InitializeAccount3` read the rent sysvar with
+//! [`Rent::get`], which must be set on the host with
+//! [`pinocchio::sysvars::host::set_sysvar`].
+//!
+//! [`Mint`]: crate::state::Mint
+//! [`TokenAccount`]: crate::state::TokenAccount
+
+mod state;
+
+use pinocchio::{
+    account_info::AccountInfo,
+    cpi::set_return_data,
+    program_error::ProgramError,
+    pubkey::Pubkey,
+    sysvars::{rent::Rent, Sysvar},
+    testing::InstructionData,
+    ProgramResult,
+};
+
+use crate::{instructions::AuthorityType, state::AccountState};
+use state::{
+    check_account_owner, load_account, load_mint, store_account, store_mint, Account, Mint,
+};
+
+/// The address of the native mint, whose token accounts hold wrapped SOL.
+pub const NATIVE_MINT_ID: Pubkey =
+    pinocchio_pubkey::pubkey!("So11111111111111111111111111111111111111112");
+
+/// The address of the incinerator, which burns the lamports it receives.
+pub const INCINERATOR_ID: Pubkey =
+    pinocchio_pubkey::pubkey!("1nc1nerator11111111111111111111111111111111");
+
+/// The address of the System program.
+const SYSTEM_PROGRAM_ID: Pubkey = [0; 32];
+
+/// Errors returned by the Token program.
+#[repr(u32)]
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+pub enum TokenError {
+    /// Lamport balance below rent-exempt threshold.
+    NotRentExempt,
+
+    /// Insufficient funds for the operation requested.
+    InsufficientFunds,
+
+    /// Invalid Mint.
+    InvalidMint,
+
+    /// Account not associated with this Mint.
+    MintMismatch,
+
+    /// Owner does not match.
+    OwnerMismatch,
+
+    /// This token's supply is fixed and new tokens cannot be minted.
+    FixedSupply,
+
+    /// The account cannot be initialized because it is already being used.
+    AlreadyInUse,
+
+    /// Invalid number of provided signers.
+    InvalidNumberOfProvidedSigners,
+
+    /// Invalid number of required signers.
+    InvalidNumberOfRequiredSigners,
+
+    /// State is uninitialized.
+    UninitializedState,
+
+    /// Instruction does not support native tokens.
+    NativeNotSupported,
+
+    /// Non-native account can only be closed if its balance is zero.
+    NonNativeHasBalance,
+
+    /// Invalid instruction.
+    InvalidInstruction,
+
+    /// State is invalid for requested operation.
+    InvalidState,
+
+    /// Operation overflowed.
+    Overflow,
+
+    /// Account does not support specified authority type.
+    AuthorityTypeNotSupported,
+
+    /// This token mint cannot freeze accounts.
+    MintCannotFreeze,
+
+    /// Account is frozen; all account operations will fail.
+    AccountFrozen,
+
+    /// Mint decimals mismatch between the client and mint.
+    MintDecimalsMismatch,
+
+    /// Instruction does not support non-native tokens.
+    NonNativeNotSupported,
+}
+
+impl From<TokenError> for ProgramError {
+    fn from(error: TokenError) -> Self {
+        ProgramError::Custom(error as u32)
+    }
+}
+
+/// Register the emulator as the Token program of the current thread.
+#[inline]
+pub fn register() {
+    pinocchio::testing::register_program(crate::ID, process_instruction);
+}
+
+/// Process a Token instruction.
+pub fn process_instruction(
+    _program_id: &Pubkey,
+    accounts: &[AccountInfo],
+    instruction_data: &[u8],
+) -> ProgramResult {
+    let (discriminator, data) = instruction_data
+        .split_first()
+        .ok_or(TokenError::InvalidInstruction)?;
+    let mut data = InstructionData::new(data, TokenError::InvalidInstruction.into());
+
+    match discriminator {
+        // InitializeMint
+        0 => {
+            let (decimals, mint_authority, freeze_authority) = initialize_mint_data(&mut data)?;
+            initialize_mint(accounts, decimals, mint_authority, freeze_authority, true)
+        }
+        // InitializeAccount
+        1 => initialize_account(accounts, None, true),
+        // Transfer
+        3 => transfer(accounts, data.u64()?, None),
+        // Approve
+        4 => approve(accounts, data.u64()?, None),
+        // Revoke
+        5 => revoke(accounts),
+        // SetAuthority
+        6 => {
+            let authority_type = match data.u8()? {
+                0 => AuthorityType::MintTokens,
+                1 => AuthorityType::FreezeAccount,
+                2 => AuthorityType::AccountOwner,
+                3 => AuthorityType::CloseAccount,
+                _ => return Err(TokenError::InvalidInstruction.into()),
+            };
+            let new_authority = pubkey_option(&mut data)?;
+            set_authority(accounts, authority_type, new_authority)
+        }
+        // MintTo
+        7 => mint_to(accounts, data.u64()?, None),
+        // Burn
+        8 => burn(accounts, data.u64()?, None),
+        // CloseAccount
+        9 => close_account(accounts),
+        // FreezeAccount
+        10 => toggle_freeze_account(accounts, true),
+        // ThawAccount
+        11 => toggle_freeze_account(accounts, false),
+        // TransferChecked
+        12 => {
+            let amount = data.u64()?;
+            transfer(accounts, amount, Some(data.u8()?))
+        }
+        // ApproveChecked
+        13 => {
+            let amount = data.u64()?;
+            approve(accounts, amount, Some(data.u8()?))
+        }
+        // MintToChecked
+        14 => {
+            let amount = data.u64()?;
+            mint_to(accounts, amount, Some(data.u8()?))
+        }
+        // BurnChecked
+        15 => {
+            let amount = data.u64()?;
+            burn(accounts, amount, Some(data.u8()?))
+        }
+        // InitializeAccount2
+        16 => initialize_account(accounts, Some(&data.pubkey()?), true),
+        // SyncNative
+        17 => sync_native(accounts),
+        // InitializeAccount3
+        18 => initialize_account(accounts, Some(&data.pubkey()?), false),
+        // InitializeMint2
+        20 => {
+            let (decimals, mint_authority, freeze_authority) = initialize_mint_data(&mut data)?;
+            initialize_mint(accounts, decimals, mint_authority, freeze_authority, false)
+        }
+        // GetAccountDataSize
+        21 => {
+            let [mint, ..] = accounts else {
+                return Err(ProgramError::NotEnoughAccountKeys);
+            };
+            check_account_owner(mint)?;
+            load_mint(mint).map_err(|_| TokenError::InvalidMint)?;
+
+            set_return_data(&(Account::LEN as u64).to_le_bytes());
+            Ok(())
+        }
+        // InitializeImmutableOwner
+        22 => {
+            let [account, ..] = accounts else {
+                return Err(ProgramError::NotEnoughAccountKeys);
+            };
+            if Account::unpack_unchecked(&account.try_borrow_data()?)?.state
+                != AccountState::Uninitialized
+            {
+                return Err(TokenError::AlreadyInUse.into());
+            }
+            Ok(())
+        }
+        _ => Err(TokenError::InvalidInstruction.into()),
+    }
+}
+
+/// Validate that `authority` is the `expected` authority and signed the
+/// instruction.
+fn validate_owner(expected: &Pubkey, authority: &AccountInfo) -> ProgramResult {
+    if expected != authority.key() {
+        return Err(TokenError::OwnerMismatch.into());
+    }
+    if !authority.is_signer() {
+        return Err(ProgramError::MissingRequiredSignature);
+    }
+    Ok(())
+}
+
+/// Return the rent sysvar, read from `account` if provided.
+fn rent(account: Option<&AccountInfo>) -> Result<Rent, ProgramError> {
+    match account {
+        Some(account) => Ok(Rent::from_account_info(account)?.clone()),
+        None => Rent::get(),
+    }
+}
+
+/// Return whether `account` is owned by the System program or the
+/// incinerator, in which case anyone may burn its tokens.
+fn is_owned_by_system_program_or_incinerator(account: &Account) -> bool {
+    account.owner == SYSTEM_PROGRAM_ID || account.owner == INCINERATOR_ID
+}
+
+fn initialize_mint(
+    accounts: &[AccountInfo],
+    decimals: u8,
+    mint_authority: Pubkey,
+    freeze_authority: Option<Pubkey>,
+    rent_sysvar_account: bool,
+) -> ProgramResult {
+    let (mint_info, rent_info) = match (accounts, rent_sysvar_account) {
+        ([mint, rent, ..], true) => (mint, Some(rent)),
+        ([mint, ..], false) => (mint, None),
+        _ => return Err(ProgramError::NotEnoughAccountKeys),
+    };
+    let rent = rent(rent_info)?;
+
+    let mut mint = Mint::unpack_unchecked(&mint_info.try_borrow_data()?)?;
+    if mint.is_initialized {
+        return Err(TokenError::AlreadyInUse.into());
+    }
+    if !rent.is_exempt(mint_info.lamports(), mint_info.data_len()) {
+        return Err(TokenError::NotRentExempt.into());
+    }
+
+    mint.mint_authority = Some(mint_authority);
+    mint.decimals = decimals;
+    mint.is_initialized = true;
+    mint.freeze_authority = freeze_authority;
+
+    store_mint(mint_info, &mint)
+}
+
+fn initialize_account(
+    accounts: &[AccountInfo],
+    owner: Option<&Pubkey>,
+    rent_sysvar_account: bool,
+) -> ProgramResult {
+    let (account_info, mint_info, owner, rent_info) = match (accounts, owner, rent_sysvar_account) {
+        ([account, mint, owner, rent, ..], None, _) => (account, mint, owner.key(), Some(rent)),
+        ([account, mint, rent, ..], Some(owner), true) => (account, mint, owner, Some(rent)),
+        ([account, mint, ..], Some(owner), false) => (account, mint, owner, None),
+        _ => return Err(ProgramError::NotEnoughAccountKeys),
+    };
+    let rent = rent(rent_info)?;
+
+    let mut account = Account::unpack_unchecked(&account_info.try_borrow_data()?)?;
+    if account.state != AccountState::Uninitialized {
+        return Err(TokenError::AlreadyInUse.into());
+    }
+    if !rent.is_exempt(account_info.lamports(), account_info.data_len()) {
+        return Err(TokenError::NotRentExempt.into());
+    }
+
+    let is_native_mint = mint_info.key() == &NATIVE_MINT_ID;
+    if !is_native_mint {
+        check_account_owner(mint_info)?;
+        load_mint(mint_info).map_err(|_| TokenError::InvalidMint)?;
+    }
+
+    account.mint = *mint_info.key();
+    account.owner = *owner;
+    account.close_authority = None;
+    account.delegate = None;
+    account.delegated_amount = 0;
+    account.state = AccountState::Initialized;
+    if is_native_mint {
+        let rent_exempt_reserve = rent.minimum_balance(account_info.data_len());
+        account.is_native = Some(rent_exempt_reserve);
+        account.amount = account_info
+            .lamports()
+            .checked_sub(rent_exempt_reserve)
+            .ok_or(TokenError::Overflow)?;
+    } else {
+        account.is_native = None;
+        account.amount = 0;
+    }
+
+    store_account(account_info, &account)
+}
+
+fn transfer(accounts: &[AccountInfo], amount: u64, expected_decimals: Option<u8>) -> ProgramResult {
+    let (source_info, mint_info, destination_info, authority_info) =
+        match (accounts, expected_decimals) {
+            ([source, mint, destination, authority, ..], Some(_)) => {
+                (source, Some(mint), destination, authority)
+            }
+            ([source, destination, authority, ..], None) => (source, None, destination, authority),
+            _ => return Err(ProgramError::NotEnoughAccountKeys),
+        };
+
+    let mut source = load_account(source_info)?;
+    let mut destination = load_account(destination_info)?;
+
+    if source.is_frozen() || destination.is_frozen() {
+        return Err(TokenError::AccountFrozen.into());
+    }
+    if source.amount < amount {
+        return Err(TokenError::InsufficientFunds.into());
+    }
+    if source.mint != destination.mint {
+        return Err(TokenError::MintMismatch.into());
+    }
+
+    if let (Some(mint_info), Some(expected_decimals)) = (mint_info, expected_decimals) {
+        if mint_info.key() != &source.mint {
+            return Err(TokenError::MintMismatch.into());
+        }
+        if load_mint(mint_info)?.decimals != expected_decimals {
+            return Err(TokenError::MintDecimalsMismatch.into());
+        }
+    }
+
+    let self_transfer = source_info.key() == destination_info.key();
+
+    match source.delegate {
+        Some(delegate) if authority_info.key() == &delegate => {
+            validate_owner(&delegate, authority_info)?;
+            if source.delegated_amount < amount {
+                return Err(TokenError::InsufficientFunds.into());
+            }
+            if !self_transfer {
+                source.delegated_amount -= amount;
+                if source.delegated_amount == 0 {
+                    source.delegate = None;
+                }
+            }
+        }
+        _ => validate_owner(&source.owner, authority_info)?,
+    }
+
+    if self_transfer || amount == 0 {
+        check_account_owner(source_info)?;
+        check_account_owner(destination_info)?;
+    }
+
+    if self_transfer {
+        return Ok(());
+    }
+
+    source.amount = source
+        .amount
+        .checked_sub(amount)
+        .ok_or(TokenError::Overflow)?;
+    destination.amount = destination
+        .amount
+        .checked_add(amount)
+        .ok_or(TokenError::Overflow)?;
+
+    if source.is_native() {
+        let mut source_lamports = source_info.try_borrow_mut_lamports()?;
+        *source_lamports = source_lamports
+            .checked_sub(amount)
+            .ok_or(TokenError::Overflow)?;
+        let mut destination_lamports = destination_info.try_borrow_mut_lamports()?;
+        *destination_lamports = destination_lamports
+            .checked_add(amount)
+            .ok_or(TokenError::Overflow)?;
+    }
+
+    store_account(source_info, &source)?;
+    store_account(destination_info, &destination)
+}
+
+fn approve(accounts: &[AccountInfo], amount: u64, expected_decimals: Option<u8>) -> ProgramResult {
+    let (source_info, mint_info, delegate_info, owner_info) = match (accounts, expected_decimals) {
+        ([source, mint, delegate, owner, ..], Some(_)) => (source, Some(mint), delegate, owner),
+        ([source, delegate, owner, ..], None) => (source, None, delegate, owner),
+        _ => return Err(ProgramError::NotEnoughAccountKeys),
+    };
+
+    let mut source = load_account(source_info)?;
+    if source.is_frozen() {
+        return Err(TokenError::AccountFrozen.into());
+    }
+
+    if let (Some(mint_info), Some(expected_decimals)) = (mint_info, expected_decimals) {
+        if mint_info.key() != &source.mint {
+            return Err(TokenError::MintMismatch.into());
+        }
+        if load_mint(mint_info)?.decimals != expected_decimals {
+            return Err(TokenError::MintDecimalsMismatch.into());
+        }
+    }
+
+    validate_owner(&source.owner, owner_info)?;
+
+    source.delegate = Some(*delegate_info.key());
+    source.delegated_amount = amount;
+
+    store_account(source_info, &source)
+}
+
+fn revoke(accounts: &[AccountInfo]) -> ProgramResult {
+    let [source_info, owner_info, ..] = accounts else {
+        return Err(ProgramError::NotEnoughAccountKeys);
+    };
+
+    let mut source = load_account(source_info)?;
+    if source.is_frozen() {
+        return Err(TokenError::AccountFrozen.into());
+    }
+
+    match source.delegate {
+        Some(delegate) if owner_info.key() == &delegate => validate_owner(&delegate, owner_info)?,
+        _ => validate_owner(&source.owner, owner_info)?,
+    }
+
+    source.delegate = None;
+    source.delegated_amount = 0;
+
+    store_account(source_info, &source)
+}
+
+fn set_authority(
+    accounts: &[AccountInfo],
+    authority_type: AuthorityType,
+    new_authority: Option<Pubkey>,
+) -> ProgramResult {
+    let [account_info, authority_info, ..] = accounts else {
+        return Err(ProgramError::NotEnoughAccountKeys);
+    };
+
+    if account_info.data_len() == Account::LEN {
+        let mut account = load_account(account_info)?;
+        if account.is_frozen() {
+            return Err(TokenError::AccountFrozen.into());
+        }
+
+        match authority_type {
+            AuthorityType::AccountOwner => {
+                validate_owner(&account.owner, authority_info)?;
+                account.owner = new_authority.ok_or(TokenError::InvalidInstruction)?;
+                account.delegate = None;
+                account.delegated_amount = 0;
+                if account.is_native() {
+                    account.close_authority = None;
+                }
+            }
+            AuthorityType::CloseAccount => {
+                validate_owner(
+                    &account.close_authority.unwrap_or(account.owner),
+                    authority_info,
+                )?;
+                account.close_authority = new_authority;
+            }
+            _ => return Err(TokenError::AuthorityTypeNotSupported.into()),
+        }
+
+        store_account(account_info, &account)
+    } else if account_info.data_len() == Mint::LEN {
+        let mut mint = load_mint(account_info)?;
+
+        match authority_type {
+            AuthorityType::MintTokens => {
+                let mint_authority = mint.mint_authority.ok_or(TokenError::FixedSupply)?;
+                validate_owner(&mint_authority, authority_info)?;
+                mint.mint_authority = new_authority;
+            }
+            AuthorityType::FreezeAccount => {
+                let freeze_authority = mint.freeze_authority.ok_or(TokenError::MintCannotFreeze)?;
+                validate_owner(&freeze_authority, authority_info)?;
+                mint.freeze_authority = new_authority;
+            }
+            _ => return Err(TokenError::AuthorityTypeNotSupported.into()),
+        }
+
+        store_mint(account_info, &mint)
+    } else {
+        Err(ProgramError::InvalidArgument)
+    }
+}
+
+fn mint_to(accounts: &[AccountInfo], amount: u64, expected_decimals: Option<u8>) -> ProgramResult {
+    let [mint_info, destination_info, owner_info, ..] = accounts else {
+        return Err(ProgramError::NotEnoughAccountKeys);
+    };
+
+    let mut destination = load_account(destination_info)?;
+    if destination.is_frozen() {
+        return Err(TokenError::AccountFrozen.into());
+    }
+    if destination.is_native() {
+        return Err(TokenError::NativeNotSupported.into());
+    }
+    if mint_info.key() != &destination.mint {
+        return Err(TokenError::MintMismatch.into());
+    }
+
+    let mut mint = load_mint(mint_info)?;
+    if let Some(expected_decimals) = expected_decimals {
+        if mint.decimals != expected_decimals {
+            return Err(TokenError::MintDecimalsMismatch.into());
+        }
+    }
+
+    match mint.mint_authority {
+        Some(mint_authority) => validate_owner(&mint_authority, owner_info)?,
+        None => return Err(TokenError::FixedSupply.into()),
+    }
+
+    if amount == 0 {
+        check_account_owner(mint_info)?;
+        check_account_owner(destination_info)?;
+    }
+
+    destination.amount = destination
+        .amount
+        .checked_add(amount)
+        .ok_or(TokenError::Overflow)?;
+    mint.supply = mint
+        .supply
+        .checked_add(amount)
+        .ok_or(TokenError::Overflow)?;
+
+    store_account(destination_info, &destination)?;
+    store_mint(mint_info, &mint)
+}
+
+fn burn(accounts: &[AccountInfo], amount: u64, expected_decimals: Option<u8>) -> ProgramResult {
+    let [source_info, mint_info, authority_info, ..] = accounts else {
+        return Err(ProgramError::NotEnoughAccountKeys);
+    };
+
+    let mut source = load_account(source_info)?;
+    let mut mint = load_mint(mint_info)?;
+
+    if source.is_frozen() {
+        return Err(TokenError::AccountFrozen.into());
+    }
+    if source.is_native() {
+        return Err(TokenError::NativeNotSupported.into());
+    }
+    if source.amount < amount {
+        return Err(TokenError::InsufficientFunds.into());
+    }
+    if mint_info.key() != &source.mint {
+        return Err(TokenError::MintMismatch.into());
+    }
+    if let Some(expected_decimals) = expected_decimals {
+        if mint.decimals != expected_decimals {
+            return Err(TokenError::MintDecimalsMismatch.into());
+        }
+    }
+
+    if !is_owned_by_system_program_or_incinerator(&source) {
+        match source.delegate {
+            Some(delegate) if authority_info.key() == &delegate => {
+                validate_owner(&delegate, authority_info)?;
+                if source.delegated_amount < amount {
+                    return Err(TokenError::InsufficientFunds.into());
+                }
+                source.delegated_amount -= amount;
+                if source.delegated_amount == 0 {
+                    source.delegate = None;
+                }
+            }
+            _ => validate_owner(&source.owner, authority_info)?,
+        }
+    }
+
+    if amount == 0 {
+        check_account_owner(source_info)?;
+        check_account_owner(mint_info)?;
+    }
+
+    source.amount = source
+        .amount
+        .checked_sub(amount)
+        .ok_or(TokenError::Overflow)?;
+    mint.supply = mint
+        .supply
+        .checked_sub(amount)
+        .ok_or(TokenError::Overflow)?;
+
+    store_account(source_info, &source)?;
+    store_mint(mint_info, &mint)
+}
+
+fn close_account(accounts: &[AccountInfo]) -> ProgramResult {
+    let [source_info, destination_info, authority_info, ..] = accounts else {
+        return Err(ProgramError::NotEnoughAccountKeys);
+    };
+
+    if source_info.key() == destination_info.key() {
+        return Err(ProgramError::InvalidAccountData);
+    }
+
+    let source = load_account(source_info)?;
+    if !source.is_native() && source.amount != 0 {
+        return Err(TokenError::NonNativeHasBalance.into());
+    }
+
+    if !is_owned_by_system_program_or_incinerator(&source) {
+        validate_owner(
+            &source.close_authority.unwrap_or(source.owner),
+            authority_info,
+        )?;
+    } else if destination_info.key() != &INCINERATOR_ID {
+        return Err(ProgramError::InvalidAccountData);
+    }
+    check_account_owner(source_info)?;
+
+    {
+        let mut destination_lamports = destination_info.try_borrow_mut_lamports()?;
+        *destination_lamports = destination_lamports
+            .checked_add(source_info.lamports())
+            .ok_or(TokenError::Overflow)?;
+    }
+    *source_info.try_borrow_mut_lamports()? = 0;
+
+    source_info.try_borrow_mut_data()?.fill(0);
+    // SAFETY: No reference to the owner is held by the emulator.
+    unsafe { source_info.assign(&SYSTEM_PROGRAM_ID) };
+    source_info.realloc(0, false)
+}
+
+fn toggle_freeze_account(accounts: &[AccountInfo], freeze: bool) -> ProgramResult {
+    let [source_info, mint_info, authority_info, ..] = accounts else {
+        return Err(ProgramError::NotEnoughAccountKeys);
+    };
+
+    let mut source = load_account(source_info)?;
+    if freeze == source.is_frozen() {
+        return Err(TokenError::InvalidState.into());
+    }
+    if source.is_native() {
+        return Err(TokenError::NativeNotSupported.into());
+    }
+    if mint_info.key() != &source.mint {
+        return Err(TokenError::MintMismatch.into());
+    }
+
+    match load_mint(mint_info)?.freeze_authority {
+        Some(freeze_authority) => validate_owner(&freeze_authority, authority_info)?,
+        None => return Err(TokenError::MintCannotFreeze.into()),
+    }
+
+    source.state = if freeze {
+        AccountState::Frozen
+    } else {
+        AccountState::Initialized
+    };
+
+    store_account(source_info, &source)
+}
+
+fn sync_native(accounts: &[AccountInfo]) -> ProgramResult {
+    let [native_info, ..] = accounts else {
+        return Err(ProgramError::NotEnoughAccountKeys);
+    };
+    check_account_owner(native_info)?;
+
+    let mut native = load_account(native_info)?;
+    let rent_exempt_reserve = native.is_native.ok_or(TokenError::NonNativeNotSupported)?;
+
+    let amount = native_info
+        .lamports()
+        .checked_sub(rent_exempt_reserve)
+        .ok_or(TokenError::Overflow)?;
+    if amount < native.amount {
+        return Err(TokenError::InvalidState.into());
+    }
+    native.amount = amount;
+
+    store_account(native_info, &native)
+}
+
+/// Read an optional public key.
+fn pubkey_option(data: &mut InstructionData) -> Result<Option<Pubkey>, ProgramError> {
+    match data.u8()? {
+        0 => Ok(None),
+        1 => data.pubkey().map(Some),
+        _ => Err(TokenError::InvalidInstruction.into()),
+    }
+}
+
+/// Read the decimals, mint authority and freeze authority of a mint.
+fn initialize_mint_data(
+    data: &mut InstructionData,
+) -> Result<(u8, Pubkey, Option<Pubkey>), ProgramError> {
+    Ok((data.u8()?, data.pubkey()?, pubkey_option(data)?))
+}
+
+#[cfg(test)]
+mod tests {
+    extern crate std;
+
+    use pinocchio::{
+        entrypoint::serialize::{InputAccount, InputBuilder},
+        sysvars::host::set_sysvar,
+        testing::{self, InputAccounts, Invoke},
+    };
+
+    use super::*;
+    use crate::{
+        instructions::{
+            Approve, Burn, CloseAccount, FreezeAccount, InitializeAccount3, InitializeMint2,
+            MintTo, ThawAccount, Transfer, TransferChecked,
+        },
+        state::TokenAccount,
+    };
+
+    const PROGRAM: Pubkey = [1; 32];
+
+    const MINT: Pubkey = [2; 32];
+
+    const AUTHORITY: Pubkey = [3; 32];
+
+    const OWNER: Pubkey = [4; 32];
+
+    /// Process an instruction of a program that calls `invoke` with its
+    /// accounts.
+    fn process(invoke: Invoke, accounts: &[AccountInfo]) -> ProgramResult {
+        testing::reset();
+        register();
+        testing::process_invoke(&PROGRAM, invoke, accounts)
+    }
+
+    fn mint_account(supply: u64) -> InputAccount {
+        let mut data = [0; Mint::LEN];
+        Mint {
+            mint_authority: Some(AUTHORITY),
+            supply,
+            decimals: 6,
+            is_initialized: true,
+            freeze_authority: Some(AUTHORITY),
+        }
+        .pack(&mut data);
+        InputAccount::new(MINT, 1, crate::ID).data(&data).writable()
+    }
+
+    fn token_account(key: Pubkey, amount: u64) -> InputAccount {
+        let mut data = [0; Account::LEN];
+        Account {
+            mint: MINT,
+            owner: OWNER,
+            amount,
+            delegate: None,
+            state: AccountState::Initialized,
+            is_native: None,
+            delegated_amount: 0,
+            close_authority: None,
+        }
+        .pack(&mut data);
+        InputAccount::new(key, 1, crate::ID).data(&data).writable()
+    }
+
+    fn amount(account: &AccountInfo) -> u64 {
+        TokenAccount::from_account_info(account).unwrap().amount()
+    }
+
+    #[test]
+    fn test_initialize() {
+        fn initialize(accounts: &[AccountInfo]) -> ProgramResult {
+            InitializeMint2 {
+                mint: &accounts[0],
+                decimals: 9,
+                mint_authority: &AUTHORITY,
+                freeze_authority: None,
+            }
+            .invoke()?;
+            InitializeAccount3 {
+                account: &accounts[1],
+                mint: &accounts[0],
+                owner: &OWNER,
+            }
+            .invoke()
+        }
+
+        set_sysvar(Rent {
+            lamports_per_byte_year: 1,
+            exemption_threshold: 2.0,
+            burn_percent: 50,
+        });
+
+        let accounts = InputAccounts::new(
+            InputBuilder::new(PROGRAM)
+                .account(
+                    InputAccount::new(MINT, 2 * (128 + 82), crate::ID)
+                        .data(&[0; Mint::LEN])
+                        .writable(),
+                )
+                .account(
+                    InputAccount::new([5; 32], 2 * (128 + 165), crate::ID)
+                        .data(&[0; Account::LEN])
+                        .writable(),
+                )
+                .build(),
+        );
+
+        assert_eq!(process(initialize, &accounts), Ok(()));
+
+        let mint = crate::state::Mint::from_account_info(&accounts[0]).unwrap();
+        assert_eq!(mint.decimals(), 9);
+        assert_eq!(mint.mint_authority(), Some(&AUTHORITY));
+        assert_eq!(mint.freeze_authority(), None);
+        drop(mint);
+
+        let account = TokenAccount::from_account_info(&accounts[1]).unwrap();
+        assert_eq!(account.mint(), &MINT);
+        assert_eq!(account.owner(), &OWNER);
+        assert_eq!(account.state(), AccountState::Initialized);
+        drop(account);
+
+        assert_eq!(
+            process(initialize, &accounts),
+            Err(TokenError::AlreadyInUse.into())
+        );
+    }
+
+    #[test]
+    fn test_mint_transfer_burn() {
+        fn mint_to(accounts: &[AccountInfo]) -> ProgramResult {
+            MintTo {
+                mint: &accounts[0],
+                account: &accounts[1],
+                mint_authority: &accounts[3],
+                amount: 100,
+            }
+            .invoke()
+        }
+
+        fn transfer(accounts: &[AccountInfo]) -> ProgramResult {
+            TransferChecked {
+                from: &accounts[1],
+                mint: &accounts[0],
+                to: &accounts[2],
+                authority: &accounts[4],
+                amount: 60,
+                decimals: 6,
+            }
+            .invoke()
+        }
+
+        fn burn(accounts: &[AccountInfo]) -> ProgramResult {
+            Burn {
+                account: &accounts[2],
+                mint: &accounts[0],
+                authority: &accounts[4],
+                amount: 10,
+            }
+            .invoke()
+        }
+
+        let accounts = InputAccounts::new(
+            InputBuilder::new(PROGRAM)
+                .account(mint_account(0))
+                .account(token_account([5; 32], 0))
+                .account(token_account([6; 32], 0))
+                .account(InputAccount::new(AUTHORITY, 0, [0; 32]).signer())
+                .account(InputAccount::new(OWNER, 0, [0; 32]).signer())
+                .build(),
+        );
+
+        assert_eq!(process(mint_to, &accounts), Ok(()));
+        assert_eq!(process(transfer, &accounts), Ok(()));
+        assert_eq!(process(burn, &accounts), Ok(()));
+
+        assert_eq!(amount(&accounts[1]), 40);
+        assert_eq!(amount(&accounts[2]), 50);
+        assert_eq!(
+            crate::state::Mint::from_account_info(&accounts[0])
+                .unwrap()
+                .supply(),
+            90
+        );
+
+        assert_eq!(
+            process(transfer, &accounts),
+            Err(TokenError::InsufficientFunds.into())
+        );
+    }
+
+    #[test]
+    fn test_delegate() {
+        fn approve(accounts: &[AccountInfo]) -> ProgramResult {
+            Approve {
+                source: &accounts[0],
+                delegate: &accounts[2],
+                authority: &accounts[3],
+                amount: 30,
+            }
+            .invoke()
+        }
+
+        fn transfer(accounts: &[AccountInfo]) -> ProgramResult {
+            Transfer {
+                from: &accounts[0],
+                to: &accounts[1],
+                authority: &accounts[2],
+                amount: 20,
+            }
+            .invoke()
+        }
+
+        let accounts = InputAccounts::new(
+            InputBuilder::new(PROGRAM)
+                .account(token_account([5; 32], 100))
+                .account(token_account([6; 32], 0))
+                .account(InputAccount::new([7; 32], 0, [0; 32]).signer())
+                .account(InputAccount::new(OWNER, 0, [0; 32]).signer())
+                .build(),
+        );
+
+        assert_eq!(
+            process(transfer, &accounts),
+            Err(TokenError::OwnerMismatch.into())
+        );
+        assert_eq!(process(approve, &accounts), Ok(()));
+        assert_eq!(process(transfer, &accounts), Ok(()));
+        assert_eq!(
+            TokenAccount::from_account_info(&accounts[0])
+                .unwrap()
+                .delegated_amount(),
+            10
+        );
+        assert_eq!(
+            process(transfer, &accounts),
+            Err(TokenError::InsufficientFunds.into())
+        );
+    }
+
+    #[test]
+    fn test_freeze_and_close() {
+        fn freeze(accounts: &[AccountInfo]) -> ProgramResult {
+            FreezeAccount {
+                account: &accounts[1],
+                mint: &accounts[0],
+                freeze_authority: &accounts[3],
+            }
+            .invoke()
+        }
+
+        fn thaw(accounts: &[AccountInfo]) -> ProgramResult {
+            ThawAccount {
+                account: &accounts[1],
+                mint: &accounts[0],
+                freeze_authority: &accounts[3],
+            }
+            .invoke()
+        }
+
+        fn close(accounts: &[AccountInfo]) -> ProgramResult {
+            CloseAccount {
+                account: &accounts[1],
+                destination: &accounts[2],
+                authority: &accounts[4],
+            }
+            .invoke()
+        }
+
+        let accounts = InputAccounts::new(
+            InputBuilder::new(PROGRAM)
+                .account(mint_account(0))
+                .account(token_account([5; 32], 0))
+                .account(InputAccount::new([6; 32], 0, [0; 32]).writable())
+                .account(InputAccount::new(AUTHORITY, 0, [0; 32]).signer())
+                .account(InputAccount::new(OWNER, 0, [0; 32]).signer())
+                .build(),
+        );
+
+        assert_eq!(process(freeze, &accounts), Ok(()));
+        assert!(TokenAccount::from_account_info(&accounts[1])
+            .unwrap()
+            .is_frozen());
+        assert_eq!(
+            process(freeze, &accounts),
+            Err(TokenError::InvalidState.into())
+        );
+        assert_eq!(process(thaw, &accounts), Ok(()));
+
+        assert_eq!(process(close, &accounts), Ok(()));
+        assert_eq!(accounts[1].lamports(), 0);
+        assert_eq!(accounts[1].data_len(), 0);
+        assert!(accounts[1].is_owned_by(&SYSTEM_PROGRAM_ID));
+        assert_eq!(accounts[2].lamports(), 1);
+    }
+
+    #[test]
+    fn test_get_account_data_size() {
+        let accounts = InputAccounts::new(
+            InputBuilder::new(crate::ID)
+                .account(mint_account(0))
+                .account(InputAccount::new([5; 32], 1, crate::ID).data(&[0; Mint::LEN]))
+                .account(InputAccount::new([6; 32], 1, crate::ID).data(&[1; 8]))
+                .build(),
+        );
+
+        testing::reset();
+        assert_eq!(
+            process_instruction(&crate::ID, &accounts[..1], &[21]),
+            Ok(())
+        );
+        assert_eq!(
+            pinocchio::cpi::get_return_data().unwrap().as_slice(),
+            (Account::LEN as u64).to_le_bytes()
+        );
+
+        // Mints that cannot be unpacked are invalid.
+        for mint in [&accounts[1..2], &accounts[2..]] {
+            assert_eq!(
+                process_instruction(&crate::ID, mint, &[21]),
+                Err(TokenError::InvalidMint.into())
+            );
+        }
+    }
+
+    #[test]
+    fn test_invalid_instruction() {
+        assert_eq!(
+            process_instruction(&crate::ID, &[], &[]),
+            Err(TokenError::InvalidInstruction.into())
+        );
+        assert_eq!(
+            process_instruction(&crate::ID, &[], &[3, 1, 0]),
+            Err(TokenError::InvalidInstruction.into())
+        );
+        assert_eq!(
+            process_instruction(&crate::ID, &[], &[2, 1]),
+            Err(TokenError::InvalidInstruction.into())
+        );
+    }
+}
@@ -0,0 +1,201 @@
+//! Unpacked mint and token account state of the Token program emulator.
+//!
+//! The state is read from and written to the account data using the layouts
+//! of [`crate::state::Mint`] and [`crate::state::TokenAccount`].
+
+use pinocchio::{account_info::AccountInfo, program_error::ProgramError, pubkey::Pubkey};
+
+use crate::state::{AccountState, Mint as MintLayout, TokenAccount as TokenAccountLayout};
+
+const _: () = assert!(MintLayout::LEN == Mint::LEN);
+const _: () = assert!(TokenAccountLayout::LEN == Account::LEN);
+
+/// Unpacked mint.
+#[derive(Clone, Debug, Default, Eq, PartialEq)]
+pub(super) struct Mint {
+    pub mint_authority: Option<Pubkey>,
+    pub supply: u64,
+    pub decimals: u8,
+    pub is_initialized: bool,
+    pub freeze_authority: Option<Pubkey>,
+}
+
+impl Mint {
+    pub const LEN: usize = 82;
+
+    /// Unpack an initialized mint.
+    pub fn unpack(data: &[u8]) -> Result<Self, ProgramError> {
+        let mint = Self::unpack_unchecked(data)?;
+        if !mint.is_initialized {
+            return Err(ProgramError::UninitializedAccount);
+        }
+        Ok(mint)
+    }
+
+    /// Unpack a mint, which may be uninitialized.
+    pub fn unpack_unchecked(data: &[u8]) -> Result<Self, ProgramError> {
+        if data.len() != Self::LEN {
+            return Err(ProgramError::InvalidAccountData);
+        }
+
+        Ok(Self {
+            mint_authority: unpack_pubkey_option(&data[0..36])?,
+            supply: unpack_u64(&data[36..44]),
+            decimals: data[44],
+            is_initialized: unpack_bool(data[45])?,
+            freeze_authority: unpack_pubkey_option(&data[46..82])?,
+        })
+    }
+
+    /// Pack the mint into `data`.
+    pub fn pack(&self, data: &mut [u8]) {
+        pack_pubkey_option(self.mint_authority.as_ref(), &mut data[0..36]);
+        data[36..44].copy_from_slice(&self.supply.to_le_bytes());
+        data[44] = self.decimals;
+        data[45] = self.is_initialized as u8;
+        pack_pubkey_option(self.freeze_authority.as_ref(), &mut data[46..82]);
+    }
+}
+
+/// Unpacked token account.
+#[derive(Clone, Debug, PartialEq)]
+pub(super) struct Account {
+    pub mint: Pubkey,
+    pub owner: Pubkey,
+    pub amount: u64,
+    pub delegate: Option<Pubkey>,
+    pub state: AccountState,
+    pub is_native: Option<u64>,
+    pub delegated_amount: u64,
+    pub close_authority: Option<Pubkey>,
+}
+
+impl Account {
+    pub const LEN: usize = 165;
+
+    /// Unpack an initialized token account.
+    pub fn unpack(data: &[u8]) -> Result<Self, ProgramError> {
+        let account = Self::unpack_unchecked(data)?;
+        if account.state == AccountState::Uninitialized {
+            return Err(ProgramError::UninitializedAccount);
+        }
+        Ok(account)
+    }
+
+    /// Unpack a token account, which may be uninitialized.
+    pub fn unpack_unchecked(data: &[u8]) -> Result<Self, ProgramError> {
+        if data.len() != Self::LEN {
+            return Err(ProgramError::InvalidAccountData);
+        }
+
+        Ok(Self {
+            mint: data[0..32].try_into().unwrap(),
+            owner: data[32..64].try_into().unwrap(),
+            amount: unpack_u64(&data[64..72]),
+            delegate: unpack_pubkey_option(&data[72..108])?,
+            state: match data[108] {
+                state @ 0..=2 => AccountState::from(state),
+                _ => return Err(ProgramError::InvalidAccountData),
+            },
+            is_native: match unpack_tag(&data[109..113])? {
+                true => Some(unpack_u64(&data[113..121])),
+                false => None,
+            },
+            delegated_amount: unpack_u64(&data[121..129]),
+            close_authority: unpack_pubkey_option(&data[129..165])?,
+        })
+    }
+
+    /// Pack the token account into `data`.
+    pub fn pack(&self, data: &mut [u8]) {
+        data[0..32].copy_from_slice(&self.mint);
+        data[32..64].copy_from_slice(&self.owner);
+        data[64..72].copy_from_slice(&self.amount.to_le_bytes());
+        pack_pubkey_option(self.delegate.as_ref(), &mut data[72..108]);
+        data[108] = self.state.into();
+        data[109..113].copy_from_slice(&[self.is_native.is_some() as u8, 0, 0, 0]);
+        data[113..121].copy_from_slice(&self.is_native.unwrap_or_default().to_le_bytes());
+        data[121..129].copy_from_slice(&self.delegated_amount.to_le_bytes());
+        pack_pubkey_option(self.close_authority.as_ref(), &mut data[129..165]);
+    }
+
+    pub fn is_frozen(&self) -> bool {
+        self.state == AccountState::Frozen
+    }
+
+    pub fn is_native(&self) -> bool {
+        self.is_native.is_some()
+    }
+}
+
+/// Unpack the initialized mint of `account`.
+pub(super) fn load_mint(account: &AccountInfo) -> Result<Mint, ProgramError> {
+    Mint::unpack(&account.try_borrow_data()?)
+}
+
+/// Unpack the initialized token account of `account`.
+pub(super) fn load_account(account: &AccountInfo) -> Result<Account, ProgramError> {
+    Account::unpack(&account.try_borrow_data()?)
+}
+
+/// Pack `mint` into the data of `account`.
+pub(super) fn store_mint(account: &AccountInfo, mint: &Mint) -> Result<(), ProgramError> {
+    check_account_owner(account)?;
+    mint.pack(&mut account.try_borrow_mut_data()?);
+    Ok(())
+}
+
+/// Pack `token_account` into the data of `account`.
+pub(super) fn store_account(
+    account: &AccountInfo,
+    token_account: &Account,
+) -> Result<(), ProgramError> {
+    check_account_owner(account)?;
+    token_account.pack(&mut account.try_borrow_mut_data()?);
+    Ok(())
+}
+
+/// Check that `account` is owned by the Token program.
+pub(super) fn check_account_owner(account: &AccountInfo) -> Result<(), ProgramError> {
+    if !account.is_owned_by(&crate::ID) {
+        return Err(ProgramError::IncorrectProgramId);
+    }
+    Ok(())
+}
+
+fn unpack_u64(data: &[u8]) -> u64 {
+    u64::from_le_bytes(data.try_into().unwrap())
+}
+
+fn unpack_bool(value: u8) -> Result<bool, ProgramError> {
+    match value {
+        0 => Ok(false),
+        1 => Ok(true),
+        _ => Err(ProgramError::InvalidAccountData),
+    }
+}
+
+fn unpack_tag(data: &[u8]) -> Result<bool, ProgramError> {
+    match data {
+        [0, 0, 0, 0] => Ok(false),
+        [1, 0, 0, 0] => Ok(true),
+        _ => Err(ProgramError::InvalidAccountData),
+    }
+}
+
+fn unpack_pubkey_option(data: &[u8]) -> Result<Option<Pubkey>, ProgramError> {
+    Ok(match unpack_tag(&data[..4])? {
+        true => Some(data[4..36].try_into().unwrap()),
+        false => None,
+    })
+}
+
+fn pack_pubkey_option(value: Option<&Pubkey>, data: &mut [u8]) {
+    match value {
+        Some(pubkey) => {
+            data[..4].copy_from_slice(&[1, 0, 0, 0]);
+            data[4..36].copy_from_slice(pubkey);
+        }
+        None => data[..36].fill(0),
+    }
+}
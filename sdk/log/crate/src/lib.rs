@@ -414,4 +414,19 @@ mod tests {
 
         str_test_case!(1, 5, 10, 50, 100, 1000, 10000);
     }
+
+    #[cfg(feature = "testing")]
+    #[test]
+    fn test_logs() {
+        use pinocchio::testing::logs::{self, Log};
+
+        logs::reset();
+
+        let mut logger = Logger::<100>::default();
+        logger.append("balance=");
+        logger.append(1_000_000_000);
+        logger.log();
+
+        assert_eq!(logs::take(), [Log::Message("balance=1000000000".into())]);
+    }
 }
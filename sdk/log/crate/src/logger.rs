@@ -118,6 +118,10 @@ impl<const BUFFER: usize> Logger<BUFFER> {
 }
 
 /// Log a message.
+///
+/// On non-`solana` targets, the message is printed to the standard output;
+/// when the `testing` feature is enabled, it is recorded by
+/// `pinocchio::testing::logs` instead.
 #[inline(always)]
 pub fn log_message(message: &[u8]) {
     #[cfg(target_os = "solana")]
@@ -126,7 +130,12 @@ pub fn log_message(message: &[u8]) {
     unsafe {
         sol_log_(message.as_ptr(), message.len() as u64);
     }
-    #[cfg(not(target_os = "solana"))]
+    #[cfg(all(not(target_os = "solana"), feature = "testing"))]
+    {
+        let message = core::str::from_utf8(message).unwrap();
+        pinocchio::log::sol_log(message);
+    }
+    #[cfg(all(not(target_os = "solana"), not(feature = "testing")))]
     {
         let message = core::str::from_utf8(message).unwrap();
         std::println!("{}", message);
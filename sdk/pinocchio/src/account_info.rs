@@ -35,10 +35,10 @@ pub(crate) struct Account {
     pub(crate) borrow_state: u8,
 
     /// Indicates whether the transaction was signed by this account.
-    is_signer: u8,
+    pub(crate) is_signer: u8,
 
     /// Indicates whether the account is writable.
-    is_writable: u8,
+    pub(crate) is_writable: u8,
 
     /// Indicates whether this account represents a program.
     executable: u8,
@@ -220,7 +220,7 @@ impl AccountInfo {
 
     /// Tries to get a read-only reference to the lamport field, failing if the
     /// field is already mutable borrowed or if 7 borrows already exist.
-    pub fn try_borrow_lamports(&self) -> Result<Ref<u64>, ProgramError> {
+    pub fn try_borrow_lamports(&self) -> Result<Ref<'_, u64>, ProgramError> {
         // check if the account lamports are already borrowed
         self.check_borrow_lamports()?;
 
@@ -239,7 +239,7 @@ impl AccountInfo {
 
     /// Tries to get a read only reference to the lamport field, failing if the field
     /// is already borrowed in any form.
-    pub fn try_borrow_mut_lamports(&self) -> Result<RefMut<u64>, ProgramError> {
+    pub fn try_borrow_mut_lamports(&self) -> Result<RefMut<'_, u64>, ProgramError> {
         // check if the account lamports are already borrowed
         self.check_borrow_mut_lamports()?;
 
@@ -291,7 +291,7 @@ impl AccountInfo {
 
     /// Tries to get a read-only reference to the data field, failing if the field
     /// is already mutable borrowed or if 7 borrows already exist.
-    pub fn try_borrow_data(&self) -> Result<Ref<[u8]>, ProgramError> {
+    pub fn try_borrow_data(&self) -> Result<Ref<'_, [u8]>, ProgramError> {
         // check if the account data is already borrowed
         self.check_borrow_data()?;
 
@@ -310,7 +310,7 @@ impl AccountInfo {
 
     /// Tries to get a mutable reference to the data field, failing if the field
     /// is already borrowed in any form.
-    pub fn try_borrow_mut_data(&self) -> Result<RefMut<[u8]>, ProgramError> {
+    pub fn try_borrow_mut_data(&self) -> Result<RefMut<'_, [u8]>, ProgramError> {
         // check if the account data is already borrowed
         self.check_borrow_mut_data()?;
 
@@ -0,0 +1,333 @@
+//! Compression of alt_bn128 points.
+//!
+//! A compressed point is the big-endian `x` coordinate with the sign of `y`
+//! stored in the most significant bits, as produced by arkworks.
+
+use super::{ALT_BN128_G1_POINT_SIZE, ALT_BN128_G2_POINT_SIZE};
+
+/// Size of a compressed G1 point.
+pub const ALT_BN128_G1_COMPRESSED_POINT_SIZE: usize = 32;
+
+/// Size of a compressed G2 point.
+pub const ALT_BN128_G2_COMPRESSED_POINT_SIZE: usize = 64;
+
+/// Operation id of the G1 compression.
+pub const ALT_BN128_G1_COMPRESS: u64 = 0;
+
+/// Operation id of the G1 decompression.
+pub const ALT_BN128_G1_DECOMPRESS: u64 = 1;
+
+/// Operation id of the G2 compression.
+pub const ALT_BN128_G2_COMPRESS: u64 = 2;
+
+/// Operation id of the G2 decompression.
+pub const ALT_BN128_G2_DECOMPRESS: u64 = 3;
+
+/// Errors returned by the alt_bn128 compression operations.
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+pub enum AltBn128CompressionError {
+    /// Unexpected error.
+    UnexpectedError,
+
+    /// Failed to decompress a G1 point.
+    G1DecompressionFailed,
+
+    /// Failed to decompress a G2 point.
+    G2DecompressionFailed,
+
+    /// Failed to compress a G1 point.
+    G1CompressionFailed,
+
+    /// Failed to compress a G2 point.
+    G2CompressionFailed,
+
+    /// Invalid input size.
+    InvalidInputSize,
+}
+
+impl From<u64> for AltBn128CompressionError {
+    fn from(error: u64) -> Self {
+        match error {
+            1 => Self::G1DecompressionFailed,
+            2 => Self::G2DecompressionFailed,
+            3 => Self::G1CompressionFailed,
+            4 => Self::G2CompressionFailed,
+            5 => Self::InvalidInputSize,
+            _ => Self::UnexpectedError,
+        }
+    }
+}
+
+impl From<AltBn128CompressionError> for u64 {
+    fn from(error: AltBn128CompressionError) -> Self {
+        match error {
+            AltBn128CompressionError::G1DecompressionFailed => 1,
+            AltBn128CompressionError::G2DecompressionFailed => 2,
+            AltBn128CompressionError::G1CompressionFailed => 3,
+            AltBn128CompressionError::G2CompressionFailed => 4,
+            AltBn128CompressionError::InvalidInputSize => 5,
+            AltBn128CompressionError::UnexpectedError => 6,
+        }
+    }
+}
+
+/// Compress a G1 point.
+///
+/// # Panics
+///
+/// On non-`solana` targets, panics if the `alt_bn128` feature is not enabled.
+#[inline]
+pub fn g1_compress(
+    point: &[u8; ALT_BN128_G1_POINT_SIZE],
+) -> Result<[u8; ALT_BN128_G1_COMPRESSED_POINT_SIZE], AltBn128CompressionError> {
+    #[cfg(target_os = "solana")]
+    {
+        // SAFETY: The output has space for the compressed point.
+        unsafe { compression_op(ALT_BN128_G1_COMPRESS, point) }
+    }
+
+    #[cfg(all(not(target_os = "solana"), feature = "alt_bn128"))]
+    {
+        host::compress::<
+            ark_bn254::G1Affine,
+            32,
+            ALT_BN128_G1_POINT_SIZE,
+            ALT_BN128_G1_COMPRESSED_POINT_SIZE,
+        >(point)
+        .ok_or(AltBn128CompressionError::G1CompressionFailed)
+    }
+
+    #[cfg(all(not(target_os = "solana"), not(feature = "alt_bn128")))]
+    {
+        core::hint::black_box(point);
+        panic!("alt_bn128 compression requires the `alt_bn128` feature on non-solana targets")
+    }
+}
+
+/// Decompress a G1 point.
+///
+/// # Panics
+///
+/// On non-`solana` targets, panics if the `alt_bn128` feature is not enabled.
+#[inline]
+pub fn g1_decompress(
+    point: &[u8; ALT_BN128_G1_COMPRESSED_POINT_SIZE],
+) -> Result<[u8; ALT_BN128_G1_POINT_SIZE], AltBn128CompressionError> {
+    #[cfg(target_os = "solana")]
+    {
+        // SAFETY: The output has space for the decompressed point.
+        unsafe { compression_op(ALT_BN128_G1_DECOMPRESS, point) }
+    }
+
+    #[cfg(all(not(target_os = "solana"), feature = "alt_bn128"))]
+    {
+        host::decompress::<
+            ark_bn254::G1Affine,
+            32,
+            ALT_BN128_G1_COMPRESSED_POINT_SIZE,
+            ALT_BN128_G1_POINT_SIZE,
+        >(point)
+        .ok_or(AltBn128CompressionError::G1DecompressionFailed)
+    }
+
+    #[cfg(all(not(target_os = "solana"), not(feature = "alt_bn128")))]
+    {
+        core::hint::black_box(point);
+        panic!("alt_bn128 compression requires the `alt_bn128` feature on non-solana targets")
+    }
+}
+
+/// Compress a G2 point.
+///
+/// # Panics
+///
+/// On non-`solana` targets, panics if the `alt_bn128` feature is not enabled.
+#[inline]
+pub fn g2_compress(
+    point: &[u8; ALT_BN128_G2_POINT_SIZE],
+) -> Result<[u8; ALT_BN128_G2_COMPRESSED_POINT_SIZE], AltBn128CompressionError> {
+    #[cfg(target_os = "solana")]
+    {
+        // SAFETY: The output has space for the compressed point.
+        unsafe { compression_op(ALT_BN128_G2_COMPRESS, point) }
+    }
+
+    #[cfg(all(not(target_os = "solana"), feature = "alt_bn128"))]
+    {
+        host::compress::<
+            ark_bn254::G2Affine,
+            64,
+            ALT_BN128_G2_POINT_SIZE,
+            ALT_BN128_G2_COMPRESSED_POINT_SIZE,
+        >(point)
+        .ok_or(AltBn128CompressionError::G2CompressionFailed)
+    }
+
+    #[cfg(all(not(target_os = "solana"), not(feature = "alt_bn128")))]
+    {
+        core::hint::black_box(point);
+        panic!("alt_bn128 compression requires the `alt_bn128` feature on non-solana targets")
+    }
+}
+
+/// Decompress a G2 point.
+///
+/// # Panics
+///
+/// On non-`solana` targets, panics if the `alt_bn128` feature is not enabled.
+#[inline]
+pub fn g2_decompress(
+    point: &[u8; ALT_BN128_G2_COMPRESSED_POINT_SIZE],
+) -> Result<[u8; ALT_BN128_G2_POINT_SIZE], AltBn128CompressionError> {
+    #[cfg(target_os = "solana")]
+    {
+        // SAFETY: The output has space for the decompressed point.
+        unsafe { compression_op(ALT_BN128_G2_DECOMPRESS, point) }
+    }
+
+    #[cfg(all(not(target_os = "solana"), feature = "alt_bn128"))]
+    {
+        host::decompress::<
+            ark_bn254::G2Affine,
+            64,
+            ALT_BN128_G2_COMPRESSED_POINT_SIZE,
+            ALT_BN128_G2_POINT_SIZE,
+        >(point)
+        .ok_or(AltBn128CompressionError::G2DecompressionFailed)
+    }
+
+    #[cfg(all(not(target_os = "solana"), not(feature = "alt_bn128")))]
+    {
+        core::hint::black_box(point);
+        panic!("alt_bn128 compression requires the `alt_bn128` feature on non-solana targets")
+    }
+}
+
+/// Apply the compression operation `op` on `input`.
+///
+/// # Safety
+///
+/// The output size `N` must match the output of the operation.
+#[cfg(target_os = "solana")]
+#[inline]
+unsafe fn compression_op<const N: usize>(
+    op: u64,
+    input: &[u8],
+) -> Result<[u8; N], AltBn128CompressionError> {
+    let mut output = core::mem::MaybeUninit::<[u8; N]>::uninit();
+    let result = crate::syscalls::sol_alt_bn128_compression(
+        op,
+        input.as_ptr(),
+        input.len() as u64,
+        output.as_mut_ptr() as *mut u8,
+    );
+
+    match result {
+        // SAFETY: The syscall has initialized the output.
+        crate::SUCCESS => Ok(output.assume_init()),
+        _ => Err(result.into()),
+    }
+}
+
+/// Compression through the arkworks serialization.
+///
+/// Both encodings are the arkworks little-endian serialization with each
+/// `CHUNK`-byte coordinate reversed.
+#[cfg(all(not(target_os = "solana"), feature = "alt_bn128"))]
+mod host {
+    use super::super::host::reverse;
+    use ark_serialize::{CanonicalDeserialize, CanonicalSerialize, Compress, Validate};
+
+    /// Compress the `IN`-byte `point` into `OUT` bytes.
+    pub(super) fn compress<P, const CHUNK: usize, const IN: usize, const OUT: usize>(
+        point: &[u8; IN],
+    ) -> Option<[u8; OUT]>
+    where
+        P: CanonicalDeserialize + CanonicalSerialize,
+    {
+        if point.iter().all(|byte| *byte == 0) {
+            return Some([0u8; OUT]);
+        }
+        let point = P::deserialize_with_mode(
+            reverse::<CHUNK, IN>(point).as_slice(),
+            Compress::No,
+            Validate::No,
+        )
+        .ok()?;
+
+        let mut compressed = [0u8; OUT];
+        point.serialize_compressed(compressed.as_mut_slice()).ok()?;
+        Some(reverse::<CHUNK, OUT>(&compressed))
+    }
+
+    /// Decompress the `IN`-byte `point` into `OUT` bytes.
+    pub(super) fn decompress<P, const CHUNK: usize, const IN: usize, const OUT: usize>(
+        point: &[u8; IN],
+    ) -> Option<[u8; OUT]>
+    where
+        P: CanonicalDeserialize + CanonicalSerialize,
+    {
+        if point.iter().all(|byte| *byte == 0) {
+            return Some([0u8; OUT]);
+        }
+        let point = P::deserialize_with_mode(
+            reverse::<CHUNK, IN>(point).as_slice(),
+            Compress::Yes,
+            Validate::No,
+        )
+        .ok()?;
+
+        let mut decompressed = [0u8; OUT];
+        point
+            .serialize_uncompressed(decompressed.as_mut_slice())
+            .ok()?;
+        Some(reverse::<CHUNK, OUT>(&decompressed))
+    }
+}
+
+#[cfg(all(test, feature = "alt_bn128"))]
+mod tests {
+    use super::*;
+    use crate::alt_bn128::{multiplication, ALT_BN128_MULTIPLICATION_INPUT_LEN};
+
+    #[test]
+    fn test_g1_compression() {
+        let mut input = [0u8; ALT_BN128_MULTIPLICATION_INPUT_LEN];
+        input[31] = 1;
+        input[63] = 2;
+
+        for scalar in [1, 2, 7] {
+            input[95] = scalar;
+            let point = multiplication(&input).unwrap();
+
+            let compressed = g1_compress(&point).unwrap();
+            assert_eq!(g1_decompress(&compressed), Ok(point));
+        }
+
+        assert_eq!(g1_compress(&[0; 64]), Ok([0; 32]));
+        assert_eq!(g1_decompress(&[0; 32]), Ok([0; 64]));
+    }
+
+    #[test]
+    fn test_g2_compression() {
+        // G2 generator
+        let point = [
+            0x19, 0x8e, 0x93, 0x93, 0x92, 0x0d, 0x48, 0x3a, 0x72, 0x60, 0xbf, 0xb7, 0x31, 0xfb,
+            0x5d, 0x25, 0xf1, 0xaa, 0x49, 0x33, 0x35, 0xa9, 0xe7, 0x12, 0x97, 0xe4, 0x85, 0xb7,
+            0xae, 0xf3, 0x12, 0xc2, 0x18, 0x00, 0xde, 0xef, 0x12, 0x1f, 0x1e, 0x76, 0x42, 0x6a,
+            0x00, 0x66, 0x5e, 0x5c, 0x44, 0x79, 0x67, 0x43, 0x22, 0xd4, 0xf7, 0x5e, 0xda, 0xdd,
+            0x46, 0xde, 0xbd, 0x5c, 0xd9, 0x92, 0xf6, 0xed, 0x09, 0x06, 0x89, 0xd0, 0x58, 0x5f,
+            0xf0, 0x75, 0xec, 0x9e, 0x99, 0xad, 0x69, 0x0c, 0x33, 0x95, 0xbc, 0x4b, 0x31, 0x33,
+            0x70, 0xb3, 0x8e, 0xf3, 0x55, 0xac, 0xda, 0xdc, 0xd1, 0x22, 0x97, 0x5b, 0x12, 0xc8,
+            0x5e, 0xa5, 0xdb, 0x8c, 0x6d, 0xeb, 0x4a, 0xab, 0x71, 0x80, 0x8d, 0xcb, 0x40, 0x8f,
+            0xe3, 0xd1, 0xe7, 0x69, 0x0c, 0x43, 0xd3, 0x7b, 0x4c, 0xe6, 0xcc, 0x01, 0x66, 0xfa,
+            0x7d, 0xaa,
+        ];
+
+        let compressed = g2_compress(&point).unwrap();
+        assert_eq!(g2_decompress(&compressed), Ok(point));
+
+        assert_eq!(g2_compress(&[0; 128]), Ok([0; 64]));
+        assert_eq!(g2_decompress(&[0; 64]), Ok([0; 128]));
+    }
+}
@@ -0,0 +1,394 @@
+//! Operations on the [alt_bn128] (BN254) elliptic curve.
+//!
+//! [alt_bn128]: https://eips.ethereum.org/EIPS/eip-196
+//!
+//! Points and scalars use the big-endian encoding of [EIP-196] and [EIP-197]:
+//! a G1 point is `x || y` and a G2 point is `x_c1 || x_c0 || y_c1 || y_c0`,
+//! each coordinate being a 32-byte big-endian field element. The point at
+//! infinity is encoded as all zeros.
+//!
+//! [EIP-196]: https://eips.ethereum.org/EIPS/eip-196
+//! [EIP-197]: https://eips.ethereum.org/EIPS/eip-197
+//!
+//! On non-`solana` targets, the operations are computed in-process when the
+//! `alt_bn128` feature is enabled.
+
+pub mod compression;
+
+/// Size of a field element.
+pub const ALT_BN128_FIELD_SIZE: usize = 32;
+
+/// Size of a G1 point.
+pub const ALT_BN128_G1_POINT_SIZE: usize = ALT_BN128_FIELD_SIZE * 2;
+
+/// Size of a G2 point.
+pub const ALT_BN128_G2_POINT_SIZE: usize = ALT_BN128_FIELD_SIZE * 4;
+
+/// Input size of the addition: two G1 points.
+pub const ALT_BN128_ADDITION_INPUT_LEN: usize = ALT_BN128_G1_POINT_SIZE * 2;
+
+/// Input size of the multiplication: a G1 point and a 32-byte scalar.
+pub const ALT_BN128_MULTIPLICATION_INPUT_LEN: usize =
+    ALT_BN128_G1_POINT_SIZE + ALT_BN128_FIELD_SIZE;
+
+/// Size of each element of the pairing input: a G1 point and a G2 point.
+pub const ALT_BN128_PAIRING_ELEMENT_LEN: usize = ALT_BN128_G1_POINT_SIZE + ALT_BN128_G2_POINT_SIZE;
+
+/// Output size of the addition.
+pub const ALT_BN128_ADDITION_OUTPUT_LEN: usize = ALT_BN128_G1_POINT_SIZE;
+
+/// Output size of the multiplication.
+pub const ALT_BN128_MULTIPLICATION_OUTPUT_LEN: usize = ALT_BN128_G1_POINT_SIZE;
+
+/// Output size of the pairing.
+pub const ALT_BN128_PAIRING_OUTPUT_LEN: usize = 32;
+
+/// Group operation id of the addition.
+pub const ALT_BN128_ADD: u64 = 0;
+
+/// Group operation id of the subtraction.
+pub const ALT_BN128_SUB: u64 = 1;
+
+/// Group operation id of the multiplication.
+pub const ALT_BN128_MUL: u64 = 2;
+
+/// Group operation id of the pairing.
+pub const ALT_BN128_PAIRING: u64 = 3;
+
+/// Errors returned by the alt_bn128 group operations.
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+pub enum AltBn128Error {
+    /// The input data is invalid.
+    InvalidInputData,
+
+    /// Invalid group data.
+    GroupError,
+
+    /// Slice data is going out of input data bounds.
+    SliceOutOfBounds,
+
+    /// Unexpected error.
+    UnexpectedError,
+
+    /// Failed to convert a byte slice into a vector.
+    TryIntoVecError,
+
+    /// Failed to convert projective to affine G1.
+    ProjectiveToG1Failed,
+}
+
+impl From<u64> for AltBn128Error {
+    fn from(error: u64) -> Self {
+        match error {
+            1 => Self::InvalidInputData,
+            2 => Self::GroupError,
+            3 => Self::SliceOutOfBounds,
+            4 => Self::TryIntoVecError,
+            5 => Self::ProjectiveToG1Failed,
+            _ => Self::UnexpectedError,
+        }
+    }
+}
+
+impl From<AltBn128Error> for u64 {
+    fn from(error: AltBn128Error) -> Self {
+        match error {
+            AltBn128Error::InvalidInputData => 1,
+            AltBn128Error::GroupError => 2,
+            AltBn128Error::SliceOutOfBounds => 3,
+            AltBn128Error::TryIntoVecError => 4,
+            AltBn128Error::ProjectiveToG1Failed => 5,
+            AltBn128Error::UnexpectedError => 6,
+        }
+    }
+}
+
+/// Add two G1 points.
+///
+/// The `input` is the concatenation of the two points.
+///
+/// # Panics
+///
+/// On non-`solana` targets, panics if the `alt_bn128` feature is not enabled.
+#[inline]
+pub fn addition(
+    input: &[u8; ALT_BN128_ADDITION_INPUT_LEN],
+) -> Result<[u8; ALT_BN128_ADDITION_OUTPUT_LEN], AltBn128Error> {
+    #[cfg(target_os = "solana")]
+    {
+        // SAFETY: The output has space for the result of the operation.
+        unsafe { group_op(ALT_BN128_ADD, input) }
+    }
+
+    #[cfg(all(not(target_os = "solana"), feature = "alt_bn128"))]
+    {
+        let (p, q) = input.split_at(ALT_BN128_G1_POINT_SIZE);
+        let sum = host::decode_g1(p)? + host::decode_g1(q)?;
+        Ok(host::encode_g1(&sum.into()))
+    }
+
+    #[cfg(all(not(target_os = "solana"), not(feature = "alt_bn128")))]
+    {
+        core::hint::black_box(input);
+        panic!("alt_bn128 addition requires the `alt_bn128` feature on non-solana targets")
+    }
+}
+
+/// Multiply a G1 point by a scalar.
+///
+/// The `input` is the concatenation of the point and the 32-byte big-endian
+/// scalar.
+///
+/// # Panics
+///
+/// On non-`solana` targets, panics if the `alt_bn128` feature is not enabled.
+#[inline]
+pub fn multiplication(
+    input: &[u8; ALT_BN128_MULTIPLICATION_INPUT_LEN],
+) -> Result<[u8; ALT_BN128_MULTIPLICATION_OUTPUT_LEN], AltBn128Error> {
+    #[cfg(target_os = "solana")]
+    {
+        // SAFETY: The output has space for the result of the operation.
+        unsafe { group_op(ALT_BN128_MUL, input) }
+    }
+
+    #[cfg(all(not(target_os = "solana"), feature = "alt_bn128"))]
+    {
+        use ark_ec::AffineRepr;
+        use ark_serialize::CanonicalDeserialize;
+
+        let (p, scalar) = input.split_at(ALT_BN128_G1_POINT_SIZE);
+        let p = host::decode_g1(p)?;
+        let scalar = ark_ff::BigInteger256::deserialize_uncompressed_unchecked(
+            host::reverse::<ALT_BN128_FIELD_SIZE, ALT_BN128_FIELD_SIZE>(scalar).as_slice(),
+        )
+        .map_err(|_| AltBn128Error::InvalidInputData)?;
+
+        Ok(host::encode_g1(&p.mul_bigint(scalar).into()))
+    }
+
+    #[cfg(all(not(target_os = "solana"), not(feature = "alt_bn128")))]
+    {
+        core::hint::black_box(input);
+        panic!("alt_bn128 multiplication requires the `alt_bn128` feature on non-solana targets")
+    }
+}
+
+/// Check whether the product of the pairings of each (G1, G2) pair of the
+/// input is the identity.
+///
+/// The `input` is a sequence of [`ALT_BN128_PAIRING_ELEMENT_LEN`] elements,
+/// each the concatenation of a G1 and a G2 point.
+///
+/// # Panics
+///
+/// On non-`solana` targets, panics if the `alt_bn128` feature is not enabled.
+#[inline]
+pub fn pairing(input: &[u8]) -> Result<bool, AltBn128Error> {
+    if !input
+        .chunks_exact(ALT_BN128_PAIRING_ELEMENT_LEN)
+        .remainder()
+        .is_empty()
+    {
+        return Err(AltBn128Error::InvalidInputData);
+    }
+
+    #[cfg(target_os = "solana")]
+    {
+        // SAFETY: The output has space for the result of the operation.
+        let result: [u8; ALT_BN128_PAIRING_OUTPUT_LEN] =
+            unsafe { group_op(ALT_BN128_PAIRING, input)? };
+        Ok(result[ALT_BN128_PAIRING_OUTPUT_LEN - 1] == 1)
+    }
+
+    #[cfg(all(not(target_os = "solana"), feature = "alt_bn128"))]
+    {
+        use ark_ec::pairing::{MillerLoopOutput, Pairing};
+        use ark_ff::One;
+
+        type Bn254 = ark_ec::bn::Bn<ark_bn254::Config>;
+
+        let product = input.chunks_exact(ALT_BN128_PAIRING_ELEMENT_LEN).try_fold(
+            ark_bn254::Fq12::one(),
+            |product, element| {
+                let (p, q) = element.split_at(ALT_BN128_G1_POINT_SIZE);
+                let miller_loop = Bn254::miller_loop(host::decode_g1(p)?, host::decode_g2(q)?);
+                Ok::<_, AltBn128Error>(product * miller_loop.0)
+            },
+        )?;
+
+        Ok(Bn254::final_exponentiation(MillerLoopOutput(product))
+            .is_some_and(|result| result.0.is_one()))
+    }
+
+    #[cfg(all(not(target_os = "solana"), not(feature = "alt_bn128")))]
+    {
+        core::hint::black_box(input);
+        panic!("alt_bn128 pairing requires the `alt_bn128` feature on non-solana targets")
+    }
+}
+
+/// Apply the group operation `op` on `input`.
+///
+/// # Safety
+///
+/// The output size `N` must match the output of the operation.
+#[cfg(target_os = "solana")]
+#[inline]
+unsafe fn group_op<const N: usize>(op: u64, input: &[u8]) -> Result<[u8; N], AltBn128Error> {
+    let mut output = core::mem::MaybeUninit::<[u8; N]>::uninit();
+    let result = crate::syscalls::sol_alt_bn128_group_op(
+        op,
+        input.as_ptr(),
+        input.len() as u64,
+        output.as_mut_ptr() as *mut u8,
+    );
+
+    match result {
+        // SAFETY: The syscall has initialized the output.
+        crate::SUCCESS => Ok(output.assume_init()),
+        _ => Err(result.into()),
+    }
+}
+
+/// Conversions between the big-endian encoding and the arkworks types.
+#[cfg(all(not(target_os = "solana"), feature = "alt_bn128"))]
+mod host {
+    use super::{
+        AltBn128Error, ALT_BN128_FIELD_SIZE, ALT_BN128_G1_POINT_SIZE, ALT_BN128_G2_POINT_SIZE,
+    };
+    use ark_bn254::{G1Affine, G2Affine};
+    use ark_ec::AffineRepr;
+    use ark_serialize::{CanonicalDeserialize, CanonicalSerialize, Compress, Validate};
+
+    /// Reverse the byte order of each `CHUNK`-byte chunk of `bytes`.
+    pub(super) fn reverse<const CHUNK: usize, const N: usize>(bytes: &[u8]) -> [u8; N] {
+        let mut reversed = [0u8; N];
+        reversed
+            .chunks_exact_mut(CHUNK)
+            .zip(bytes.chunks_exact(CHUNK))
+            .for_each(|(target, source)| {
+                target.copy_from_slice(source);
+                target.reverse();
+            });
+        reversed
+    }
+
+    /// Decode a big-endian G1 point, validating that it is in the group.
+    pub(super) fn decode_g1(bytes: &[u8]) -> Result<G1Affine, AltBn128Error> {
+        if bytes.iter().all(|byte| *byte == 0) {
+            return Ok(G1Affine::zero());
+        }
+        let le_bytes = reverse::<ALT_BN128_FIELD_SIZE, ALT_BN128_G1_POINT_SIZE>(bytes);
+        G1Affine::deserialize_with_mode(le_bytes.as_slice(), Compress::No, Validate::Yes)
+            .map_err(|_| AltBn128Error::InvalidInputData)
+    }
+
+    /// Decode a big-endian G2 point, validating that it is in the group.
+    pub(super) fn decode_g2(bytes: &[u8]) -> Result<G2Affine, AltBn128Error> {
+        if bytes.iter().all(|byte| *byte == 0) {
+            return Ok(G2Affine::zero());
+        }
+        // Each coordinate is encoded as `c1 || c0`, while arkworks expects
+        // `c0 || c1`; reversing each coordinate swaps the components.
+        let le_bytes = reverse::<{ ALT_BN128_FIELD_SIZE * 2 }, ALT_BN128_G2_POINT_SIZE>(bytes);
+        G2Affine::deserialize_with_mode(le_bytes.as_slice(), Compress::No, Validate::Yes)
+            .map_err(|_| AltBn128Error::InvalidInputData)
+    }
+
+    /// Encode a G1 point in big-endian.
+    pub(super) fn encode_g1(point: &G1Affine) -> [u8; ALT_BN128_G1_POINT_SIZE] {
+        let mut le_bytes = [0u8; ALT_BN128_G1_POINT_SIZE];
+        // The point at infinity is encoded with zero coordinates.
+        if let Some((x, y)) = point.xy() {
+            x.serialize_uncompressed(&mut le_bytes[..ALT_BN128_FIELD_SIZE])
+                .and_then(|_| y.serialize_uncompressed(&mut le_bytes[ALT_BN128_FIELD_SIZE..]))
+                .expect("field elements fit in 32 bytes");
+        }
+        reverse::<ALT_BN128_FIELD_SIZE, ALT_BN128_G1_POINT_SIZE>(&le_bytes)
+    }
+}
+
+#[cfg(all(test, feature = "alt_bn128"))]
+mod tests {
+    use super::*;
+
+    /// Big-endian encoding of the G1 generator `(1, 2)`.
+    fn g1_generator() -> [u8; ALT_BN128_G1_POINT_SIZE] {
+        let mut point = [0u8; ALT_BN128_G1_POINT_SIZE];
+        point[ALT_BN128_FIELD_SIZE - 1] = 1;
+        point[ALT_BN128_G1_POINT_SIZE - 1] = 2;
+        point
+    }
+
+    /// Big-endian encoding of the G2 generator.
+    fn g2_generator() -> [u8; ALT_BN128_G2_POINT_SIZE] {
+        use ark_ec::AffineRepr;
+        use ark_serialize::CanonicalSerialize;
+
+        let generator = ark_bn254::G2Affine::generator();
+        let (x, y) = generator.xy().unwrap();
+        let mut le_bytes = [0u8; ALT_BN128_G2_POINT_SIZE];
+        x.serialize_uncompressed(&mut le_bytes[..64]).unwrap();
+        y.serialize_uncompressed(&mut le_bytes[64..]).unwrap();
+        host::reverse::<64, ALT_BN128_G2_POINT_SIZE>(&le_bytes)
+    }
+
+    #[test]
+    fn test_addition_multiplication() {
+        let g = g1_generator();
+
+        let mut input = [0u8; ALT_BN128_ADDITION_INPUT_LEN];
+        input[..64].copy_from_slice(&g);
+        input[64..].copy_from_slice(&g);
+        let sum = addition(&input).unwrap();
+
+        let mut input = [0u8; ALT_BN128_MULTIPLICATION_INPUT_LEN];
+        input[..64].copy_from_slice(&g);
+        input[95] = 2;
+        assert_eq!(multiplication(&input), Ok(sum));
+
+        // adding the point at infinity
+        let mut input = [0u8; ALT_BN128_ADDITION_INPUT_LEN];
+        input[..64].copy_from_slice(&sum);
+        assert_eq!(addition(&input), Ok(sum));
+
+        // multiplying by zero
+        let mut input = [0u8; ALT_BN128_MULTIPLICATION_INPUT_LEN];
+        input[..64].copy_from_slice(&g);
+        assert_eq!(multiplication(&input), Ok([0u8; 64]));
+
+        // (1, 3) is not on the curve
+        let mut input = [0u8; ALT_BN128_ADDITION_INPUT_LEN];
+        input[..64].copy_from_slice(&g);
+        input[63] = 3;
+        assert_eq!(addition(&input), Err(AltBn128Error::InvalidInputData));
+    }
+
+    #[test]
+    fn test_pairing() {
+        let g1 = g1_generator();
+        let g2 = g2_generator();
+
+        // multiplying by `r - 1` yields -G1
+        let mut input = [0u8; ALT_BN128_MULTIPLICATION_INPUT_LEN];
+        input[..64].copy_from_slice(&g1);
+        input[64..].copy_from_slice(&[
+            0x30, 0x64, 0x4e, 0x72, 0xe1, 0x31, 0xa0, 0x29, 0xb8, 0x50, 0x45, 0xb6, 0x81, 0x81,
+            0x58, 0x5d, 0x28, 0x33, 0xe8, 0x48, 0x79, 0xb9, 0x70, 0x91, 0x43, 0xe1, 0xf5, 0x93,
+            0xf0, 0x00, 0x00, 0x00,
+        ]);
+        let neg_g1 = multiplication(&input).unwrap();
+
+        let mut input = [0u8; ALT_BN128_PAIRING_ELEMENT_LEN * 2];
+        input[..64].copy_from_slice(&g1);
+        input[64..192].copy_from_slice(&g2);
+        input[192..256].copy_from_slice(&neg_g1);
+        input[256..].copy_from_slice(&g2);
+
+        assert_eq!(pairing(&input), Ok(true));
+        assert_eq!(pairing(&input[..ALT_BN128_PAIRING_ELEMENT_LEN]), Ok(false));
+        assert_eq!(pairing(&[]), Ok(true));
+        assert_eq!(pairing(&input[1..]), Err(AltBn128Error::InvalidInputData));
+    }
+}
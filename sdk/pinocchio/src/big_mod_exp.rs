@@ -0,0 +1,168 @@
+//! Modular exponentiation of big unsigned integers.
+//!
+//! Integers are encoded as big-endian byte arrays of arbitrary length. On
+//! non-`solana` targets, the exponentiation is computed in-process when the
+//! `big_mod_exp` feature is enabled.
+
+use crate::program_error::ProgramError;
+
+/// Maximum length, in bytes, of each of the base, exponent and modulus.
+pub const MAX_BIG_MOD_EXP_LEN: usize = 512;
+
+/// Parameters of the `sol_big_mod_exp` syscall.
+#[repr(C)]
+pub struct BigModExpParams {
+    /// Address of the base.
+    pub base: *const u8,
+
+    /// Length of the base.
+    pub base_len: u64,
+
+    /// Address of the exponent.
+    pub exponent: *const u8,
+
+    /// Length of the exponent.
+    pub exponent_len: u64,
+
+    /// Address of the modulus.
+    pub modulus: *const u8,
+
+    /// Length of the modulus.
+    pub modulus_len: u64,
+}
+
+/// Compute `base ^ exponent % modulus`.
+///
+/// The result has the same length as the modulus, padded with leading zeros.
+/// A zero or one modulus yields zero.
+///
+/// # Errors
+///
+/// Returns [`ProgramError::InvalidArgument`] if any of `base`, `exponent` or
+/// `modulus` is longer than [`MAX_BIG_MOD_EXP_LEN`] bytes, which would
+/// otherwise abort the program.
+///
+/// # Panics
+///
+/// On non-`solana` targets, panics if the `big_mod_exp` feature is not enabled.
+#[inline]
+pub fn big_mod_exp<const MODULUS_LEN: usize>(
+    base: &[u8],
+    exponent: &[u8],
+    modulus: &[u8; MODULUS_LEN],
+) -> Result<[u8; MODULUS_LEN], ProgramError> {
+    if base.len() > MAX_BIG_MOD_EXP_LEN
+        || exponent.len() > MAX_BIG_MOD_EXP_LEN
+        || MODULUS_LEN > MAX_BIG_MOD_EXP_LEN
+    {
+        return Err(ProgramError::InvalidArgument);
+    }
+
+    #[cfg(target_os = "solana")]
+    {
+        let mut result = core::mem::MaybeUninit::<[u8; MODULUS_LEN]>::uninit();
+        let params = BigModExpParams {
+            base: base.as_ptr(),
+            base_len: base.len() as u64,
+            exponent: exponent.as_ptr(),
+            exponent_len: exponent.len() as u64,
+            modulus: modulus.as_ptr(),
+            modulus_len: MODULUS_LEN as u64,
+        };
+
+        // SAFETY: `result` has space for `modulus_len` bytes, which are
+        // all written by the syscall.
+        unsafe {
+            crate::syscalls::sol_big_mod_exp(
+                &params as *const _ as *const u8,
+                result.as_mut_ptr() as *mut u8,
+            );
+            Ok(result.assume_init())
+        }
+    }
+
+    #[cfg(all(not(target_os = "solana"), feature = "big_mod_exp"))]
+    {
+        use num_bigint::BigUint;
+
+        let mut result = [0u8; MODULUS_LEN];
+        let modulus = BigUint::from_bytes_be(modulus);
+
+        if modulus > BigUint::from(1u8) {
+            let value = BigUint::from_bytes_be(base)
+                .modpow(&BigUint::from_bytes_be(exponent), &modulus)
+                .to_bytes_be();
+            result[MODULUS_LEN - value.len()..].copy_from_slice(&value);
+        }
+
+        Ok(result)
+    }
+
+    #[cfg(all(not(target_os = "solana"), not(feature = "big_mod_exp")))]
+    {
+        core::hint::black_box((base, exponent, modulus));
+        panic!("big_mod_exp requires the `big_mod_exp` feature on non-solana targets")
+    }
+}
+
+#[cfg(all(test, feature = "big_mod_exp"))]
+mod tests {
+    use super::*;
+
+    fn decode_hex<const N: usize>(hex: &str) -> [u8; N] {
+        let mut bytes = [0u8; N];
+        bytes.iter_mut().enumerate().for_each(|(i, byte)| {
+            *byte = u8::from_str_radix(&hex[i * 2..i * 2 + 2], 16).unwrap();
+        });
+        bytes
+    }
+
+    #[test]
+    fn test_big_mod_exp() {
+        let test_cases = [
+            (
+                "1111111111111111111111111111111111111111111111111111111111111111",
+                "1111111111111111111111111111111111111111111111111111111111111111",
+                "111111111111111111111111111111111111111111111111111111111111110A",
+                "0A7074864588D6847F33A168209E516F60005A0CEC3F33AAF70E8002FE964BCD",
+            ),
+            (
+                "9874231472317432847923174392874918237439287492374932871937289719",
+                "0948403985401232889438579475812347232099080051356165126166266222",
+                "25532321a214321423124212222224222b242222222222222222222222222444",
+                "220ECE1C42624E98AEE7EB86578B2FE5C4855DFFACCB43CCBB708A3AB37F184D",
+            ),
+            (
+                "0000000000000000000000000000000000000000000000000000000000000005",
+                "0000000000000000000000000000000000000000000000000000000000000002",
+                "0000000000000000000000000000000000000000000000000000000000000007",
+                "0000000000000000000000000000000000000000000000000000000000000004",
+            ),
+            (
+                "0000000000000000000000000000000000000000000000000000000000000019",
+                "0000000000000000000000000000000000000000000000000000000000000019",
+                "0000000000000000000000000000000000000000000000000000000000000000",
+                "0000000000000000000000000000000000000000000000000000000000000000",
+            ),
+        ];
+
+        for (base, exponent, modulus, expected) in test_cases {
+            assert_eq!(
+                big_mod_exp(
+                    &decode_hex::<32>(base),
+                    &decode_hex::<32>(exponent),
+                    &decode_hex::<32>(modulus)
+                ),
+                Ok(decode_hex::<32>(expected))
+            );
+        }
+
+        // inputs of different lengths
+        assert_eq!(big_mod_exp(&[3], &[0, 2], &[0, 0, 0, 5]), Ok([0, 0, 0, 4]));
+
+        assert_eq!(
+            big_mod_exp(&[0; MAX_BIG_MOD_EXP_LEN + 1], &[1], &[7]),
+            Err(ProgramError::InvalidArgument)
+        );
+    }
+}
@@ -0,0 +1,151 @@
+//! Compute units introspection.
+//!
+//! [`remaining_compute_units`] returns the compute units left in the current
+//! transaction, while a [`CuMeter`] logs the units consumed by a scope:
+//!
+//! ```
+//! use pinocchio::compute_units::CuMeter;
+//!
+//! {
+//!     let _meter = CuMeter::new("verify");
+//!     // ...
+//! } // Logs "verify: <units>".
+//! ```
+
+use crate::log::sol_log;
+
+/// Maximum length of a [`CuMeter`] log message; longer labels are truncated.
+pub const MAX_LOG_LEN: usize = 128;
+
+/// Maximum number of digits of a `u64` value.
+const MAX_U64_DIGITS: usize = 20;
+
+/// Separator between the label and the units of a [`CuMeter`] log message.
+const SEPARATOR: &[u8] = b": ";
+
+/// Return the remaining compute units the program may consume.
+///
+/// On non-`solana` targets, the compute units are read from the meter of
+/// [`crate::testing::compute_budget`] when the `testing` feature is enabled;
+/// otherwise this function always returns `0`.
+#[inline(always)]
+pub fn remaining_compute_units() -> u64 {
+    #[cfg(target_os = "solana")]
+    unsafe {
+        crate::syscalls::sol_remaining_compute_units()
+    }
+
+    #[cfg(all(not(target_os = "solana"), feature = "testing"))]
+    {
+        use crate::testing::compute_budget::{consume, remaining, SYSCALL_BASE_COST};
+
+        consume(SYSCALL_BASE_COST);
+        remaining()
+    }
+
+    #[cfg(all(not(target_os = "solana"), not(feature = "testing")))]
+    0
+}
+
+/// Guard that logs the compute units consumed by a scope when dropped.
+///
+/// The log message is the label followed by the consumed units, e.g.
+/// `"transfer: 1523"`. The units include the cost of the
+/// `sol_remaining_compute_units` syscall issued on drop.
+pub struct CuMeter<'a> {
+    /// The label of the log message.
+    label: &'a str,
+
+    /// The remaining compute units when the meter was created.
+    start: u64,
+}
+
+impl<'a> CuMeter<'a> {
+    /// Start metering the compute units consumed until the meter is dropped.
+    #[inline(always)]
+    pub fn new(label: &'a str) -> Self {
+        Self {
+            label,
+            start: remaining_compute_units(),
+        }
+    }
+
+    /// Return the compute units consumed since the meter was created.
+    #[inline(always)]
+    pub fn consumed(&self) -> u64 {
+        self.start.saturating_sub(remaining_compute_units())
+    }
+}
+
+impl Drop for CuMeter<'_> {
+    fn drop(&mut self) {
+        let mut buffer = [0u8; MAX_LOG_LEN];
+        sol_log(format_log(self.label, self.consumed(), &mut buffer));
+    }
+}
+
+/// Write `"<label>: <units>"` into `buffer`, truncating the label if needed.
+fn format_log<'b>(label: &str, units: u64, buffer: &'b mut [u8; MAX_LOG_LEN]) -> &'b str {
+    let mut label_len = label
+        .len()
+        .min(MAX_LOG_LEN - SEPARATOR.len() - MAX_U64_DIGITS);
+    while !label.is_char_boundary(label_len) {
+        label_len -= 1;
+    }
+
+    let mut digits = [0u8; MAX_U64_DIGITS];
+    let mut digits_start = MAX_U64_DIGITS;
+    let mut value = units;
+    loop {
+        digits_start -= 1;
+        digits[digits_start] = b'0' + (value % 10) as u8;
+        value /= 10;
+        if value == 0 {
+            break;
+        }
+    }
+
+    let mut len = 0;
+    for part in [
+        &label.as_bytes()[..label_len],
+        SEPARATOR,
+        &digits[digits_start..],
+    ] {
+        buffer[len..len + part.len()].copy_from_slice(part);
+        len += part.len();
+    }
+
+    // SAFETY: The buffer contains a prefix of `label` ending on a char boundary,
+    // followed by ASCII characters.
+    unsafe { core::str::from_utf8_unchecked(&buffer[..len]) }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_format_log() {
+        let mut buffer = [0u8; MAX_LOG_LEN];
+        assert_eq!(format_log("transfer", 1523, &mut buffer), "transfer: 1523");
+
+        let mut buffer = [0u8; MAX_LOG_LEN];
+        assert_eq!(format_log("", 0, &mut buffer), ": 0");
+
+        let mut buffer = [0u8; MAX_LOG_LEN];
+        assert_eq!(
+            format_log("max", u64::MAX, &mut buffer),
+            "max: 18446744073709551615"
+        );
+
+        // Labels are truncated on a char boundary.
+        let mut label = [0u8; 3 * MAX_LOG_LEN];
+        label
+            .chunks_exact_mut(3)
+            .for_each(|char| char.copy_from_slice("€".as_bytes()));
+        let mut buffer = [0u8; MAX_LOG_LEN];
+        let log = format_log(core::str::from_utf8(&label).unwrap(), 7, &mut buffer);
+        assert!(log.ends_with("€: 7"));
+        assert!(log.len() <= MAX_LOG_LEN);
+    }
+}
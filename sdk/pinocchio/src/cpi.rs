@@ -22,6 +22,7 @@ pub const MAX_CPI_ACCOUNTS: usize = 64;
 /// discarded immediately after.
 #[repr(C)]
 #[derive(Debug, PartialEq, Clone)]
+#[cfg_attr(not(target_os = "solana"), allow(dead_code))]
 struct CInstruction<'a> {
     /// Public key of the program.
     program_id: *const Pubkey,
@@ -114,14 +115,12 @@ pub fn invoke_signed<const ACCOUNTS: usize>(
     }
 
     unsafe {
-        invoke_signed_unchecked(
+        invoke_signed_accounts(
             instruction,
             core::slice::from_raw_parts(accounts.as_ptr() as _, ACCOUNTS),
             signers_seeds,
-        );
+        )
     }
-
-    Ok(())
 }
 
 /// Invoke a cross-program instruction with signatures from a slice of
@@ -172,14 +171,38 @@ pub fn slice_invoke_signed(
     }
     // SAFETY: The accounts have been validated.
     unsafe {
-        invoke_signed_unchecked(
+        invoke_signed_accounts(
             instruction,
             core::slice::from_raw_parts(accounts.as_ptr() as _, len),
             signers_seeds,
-        );
+        )
     }
+}
 
-    Ok(())
+/// Invoke a cross-program instruction with validated accounts.
+///
+/// On non-`solana` targets with the `testing` feature enabled, errors of the
+/// invoked program are returned instead of aborting.
+///
+/// # Safety
+///
+/// The accounts must have been validated as in [`slice_invoke_signed`].
+#[inline(always)]
+unsafe fn invoke_signed_accounts(
+    instruction: &Instruction,
+    accounts: &[Account],
+    signers_seeds: &[Signer],
+) -> ProgramResult {
+    #[cfg(all(not(target_os = "solana"), feature = "testing"))]
+    {
+        crate::testing::invoke_signed(instruction, accounts, signers_seeds)
+    }
+
+    #[cfg(not(all(not(target_os = "solana"), feature = "testing")))]
+    {
+        invoke_signed_unchecked(instruction, accounts, signers_seeds);
+        Ok(())
+    }
 }
 
 /// Invoke a cross-program instruction but don't enforce Rust's aliasing rules.
@@ -229,10 +252,92 @@ pub unsafe fn invoke_signed_unchecked(
         };
     }
 
-    #[cfg(not(target_os = "solana"))]
+    #[cfg(all(not(target_os = "solana"), feature = "testing"))]
+    if let Err(error) = crate::testing::invoke_signed(instruction, accounts, signers_seeds) {
+        panic!("cross-program invocation failed: {:?}", error);
+    }
+
+    #[cfg(all(not(target_os = "solana"), not(feature = "testing")))]
     core::hint::black_box((instruction, accounts, signers_seeds));
 }
 
+/// Stack height of an instruction invoked directly by the transaction.
+///
+/// Each cross-program invocation increments the stack height by one.
+pub const TRANSACTION_LEVEL_STACK_HEIGHT: usize = 1;
+
+/// Maximum stack height of an instruction.
+///
+/// A cross-program invocation from an instruction at this height fails.
+pub const MAX_INVOKE_STACK_HEIGHT: usize = 5;
+
+#[cfg(all(not(target_os = "solana"), feature = "std"))]
+std::thread_local! {
+    static STACK_HEIGHT: core::cell::Cell<usize> =
+        const { core::cell::Cell::new(TRANSACTION_LEVEL_STACK_HEIGHT) };
+}
+
+/// Get the current stack height.
+///
+/// The stack height is [`TRANSACTION_LEVEL_STACK_HEIGHT`] for instructions
+/// invoked directly by the transaction and increases by one for each level of
+/// cross-program invocation.
+///
+/// On non-`solana` targets, it returns the value set with [`set_stack_height`]
+/// and reset with [`reset_stack_height`] when the `std` feature is enabled,
+/// and [`TRANSACTION_LEVEL_STACK_HEIGHT`] otherwise.
+#[inline(always)]
+pub fn get_stack_height() -> usize {
+    #[cfg(target_os = "solana")]
+    unsafe {
+        crate::syscalls::sol_get_stack_height() as usize
+    }
+
+    #[cfg(all(not(target_os = "solana"), feature = "std"))]
+    {
+        STACK_HEIGHT.with(|stack_height| stack_height.get())
+    }
+
+    #[cfg(all(not(target_os = "solana"), not(feature = "std")))]
+    {
+        TRANSACTION_LEVEL_STACK_HEIGHT
+    }
+}
+
+/// Set the value returned by [`get_stack_height`] on the current thread.
+///
+/// This is only available on non-`solana` targets, where there is no runtime
+/// to track the stack height.
+#[cfg(all(not(target_os = "solana"), feature = "std"))]
+pub fn set_stack_height(stack_height: usize) {
+    STACK_HEIGHT.with(|height| height.set(stack_height));
+}
+
+/// Reset the value returned by [`get_stack_height`] on the current thread to
+/// [`TRANSACTION_LEVEL_STACK_HEIGHT`].
+///
+/// This is only available on non-`solana` targets.
+#[cfg(all(not(target_os = "solana"), feature = "std"))]
+pub fn reset_stack_height() {
+    set_stack_height(TRANSACTION_LEVEL_STACK_HEIGHT);
+}
+
+/// Check that the program was invoked directly by the transaction and not
+/// through a cross-program invocation.
+///
+/// # Errors
+///
+/// Returns [`ProgramError::InvalidArgument`] if the current stack height is not
+/// [`TRANSACTION_LEVEL_STACK_HEIGHT`].
+#[inline(always)]
+pub fn assert_top_level() -> ProgramResult {
+    if get_stack_height() == TRANSACTION_LEVEL_STACK_HEIGHT {
+        Ok(())
+    } else {
+        Err(ProgramError::InvalidArgument)
+    }
+}
+
 /// Maximum size that can be set using [`set_return_data`].
 pub const MAX_RETURN_DATA: usize = 1024;
 
@@ -249,7 +354,10 @@ pub fn set_return_data(data: &[u8]) {
         crate::syscalls::sol_set_return_data(data.as_ptr(), data.len() as u64)
     };
 
-    #[cfg(not(target_os = "solana"))]
+    #[cfg(all(not(target_os = "solana"), feature = "testing"))]
+    crate::testing::set_return_data(data);
+
+    #[cfg(all(not(target_os = "solana"), not(feature = "testing")))]
     core::hint::black_box(data);
 }
 
@@ -308,10 +416,97 @@ pub fn get_return_data() -> Option<ReturnData> {
         }
     }
 
-    #[cfg(not(target_os = "solana"))]
+    #[cfg(all(not(target_os = "solana"), feature = "testing"))]
+    {
+        let (program_id, return_data) = crate::testing::get_return_data();
+
+        if return_data.is_empty() {
+            None
+        } else {
+            let mut data = [core::mem::MaybeUninit::<u8>::uninit(); MAX_RETURN_DATA];
+            data.iter_mut()
+                .zip(return_data.iter())
+                .for_each(|(byte, value)| {
+                    byte.write(*value);
+                });
+
+            Some(ReturnData {
+                program_id,
+                data,
+                size: return_data.len(),
+            })
+        }
+    }
+
+    #[cfg(all(not(target_os = "solana"), not(feature = "testing")))]
     core::hint::black_box(None)
 }
 
+/// A type that can be passed as return data.
+///
+/// Values are copied byte-for-byte by [`set_return_data_as`] and
+/// [`get_return_data_as`].
+///
+/// # Safety
+///
+/// Implementors must be `#[repr(C)]` or `#[repr(transparent)]` types without
+/// padding bytes, for which any bit pattern is a valid value.
+pub unsafe trait ReturnDataValue: Copy {}
+
+macro_rules! impl_return_data_value {
+    ( $( $type:ty ),* ) => {
+        $( unsafe impl ReturnDataValue for $type {} )*
+    };
+}
+
+impl_return_data_value!(u8, u16, u32, u64, u128, i8, i16, i32, i64, i128);
+
+unsafe impl<T: ReturnDataValue, const N: usize> ReturnDataValue for [T; N] {}
+
+/// Set the running program's return data to the bytes of `value`.
+///
+/// The value is read by the caller with [`get_return_data_as`].
+#[inline]
+pub fn set_return_data_as<T: ReturnDataValue>(value: &T) {
+    const { assert!(core::mem::size_of::<T>() <= MAX_RETURN_DATA) };
+
+    // SAFETY: `T` has no padding bytes, so all its bytes are initialized.
+    set_return_data(unsafe {
+        core::slice::from_raw_parts(value as *const T as *const u8, core::mem::size_of::<T>())
+    });
+}
+
+/// Get the return data set by `program_id` as a value of type `T`.
+///
+/// # Errors
+///
+/// Returns [`ProgramError::IncorrectProgramId`] if there is no return data or
+/// it was not set by `program_id`, and [`ProgramError::InvalidArgument`] if its
+/// length does not match the size of `T`.
+#[inline]
+pub fn get_return_data_as<T: ReturnDataValue>(program_id: &Pubkey) -> Result<T, ProgramError> {
+    read_return_data(get_return_data(), program_id)
+}
+
+/// Read a value of type `T` from `return_data` set by `program_id`.
+#[inline(always)]
+fn read_return_data<T: ReturnDataValue>(
+    return_data: Option<ReturnData>,
+    program_id: &Pubkey,
+) -> Result<T, ProgramError> {
+    let return_data = match return_data {
+        Some(return_data) if return_data.program_id() == program_id => return_data,
+        _ => return Err(ProgramError::IncorrectProgramId),
+    };
+
+    if return_data.len() != core::mem::size_of::<T>() {
+        return Err(ProgramError::InvalidArgument);
+    }
+
+    // SAFETY: The return data has the size of `T`, and any bit pattern is a valid `T`.
+    Ok(unsafe { core::ptr::read_unaligned(return_data.as_ptr() as *const T) })
+}
+
 /// Struct to hold the return data from an invoked program.
 pub struct ReturnData {
     /// Program that most recently set the return data.
@@ -343,3 +538,64 @@ impl Deref for ReturnData {
         self.as_slice()
     }
 }
+
+#[cfg(all(test, feature = "std"))]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_assert_top_level() {
+        assert_eq!(get_stack_height(), TRANSACTION_LEVEL_STACK_HEIGHT);
+        assert_eq!(assert_top_level(), Ok(()));
+
+        set_stack_height(TRANSACTION_LEVEL_STACK_HEIGHT + 1);
+
+        assert_eq!(get_stack_height(), 2);
+        assert_eq!(assert_top_level(), Err(ProgramError::InvalidArgument));
+
+        reset_stack_height();
+
+        assert_eq!(get_stack_height(), TRANSACTION_LEVEL_STACK_HEIGHT);
+    }
+
+    #[test]
+    fn test_read_return_data() {
+        let program_id = [1; 32];
+        let value: [u64; 2] = [7, u64::MAX];
+
+        let mut return_data = ReturnData {
+            program_id,
+            data: [MaybeUninit::uninit(); MAX_RETURN_DATA],
+            size: 16,
+        };
+        return_data.data[..16]
+            .iter_mut()
+            .zip(value.iter().flat_map(|v| v.to_le_bytes()))
+            .for_each(|(byte, value)| {
+                byte.write(value);
+            });
+
+        let clone = |return_data: &ReturnData| ReturnData {
+            program_id: return_data.program_id,
+            data: return_data.data,
+            size: return_data.size,
+        };
+
+        assert_eq!(
+            read_return_data::<[u64; 2]>(Some(clone(&return_data)), &program_id),
+            Ok(value)
+        );
+        assert_eq!(
+            read_return_data::<u64>(Some(clone(&return_data)), &program_id),
+            Err(ProgramError::InvalidArgument)
+        );
+        assert_eq!(
+            read_return_data::<[u64; 2]>(Some(return_data), &[2; 32]),
+            Err(ProgramError::IncorrectProgramId)
+        );
+        assert_eq!(
+            read_return_data::<[u64; 2]>(None, &program_id),
+            Err(ProgramError::IncorrectProgramId)
+        );
+    }
+}
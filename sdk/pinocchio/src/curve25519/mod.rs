@@ -0,0 +1,106 @@
+//! Group operations on the Curve25519 [Edwards] and [Ristretto] representations.
+//!
+//! [Edwards]: https://en.wikipedia.org/wiki/Twisted_Edwards_curve
+//! [Ristretto]: https://ristretto.group
+//!
+//! Points and scalars are represented by their 32-byte encodings. On non-`solana`
+//! targets, the operations are computed in-process when the `curve25519` feature
+//! is enabled.
+
+pub mod edwards;
+pub mod ristretto;
+
+/// Curve id of the Edwards representation.
+pub const CURVE25519_EDWARDS: u64 = 0;
+
+/// Curve id of the Ristretto representation.
+pub const CURVE25519_RISTRETTO: u64 = 1;
+
+/// Group operation id of point addition.
+pub const ADD: u64 = 0;
+
+/// Group operation id of point subtraction.
+pub const SUB: u64 = 1;
+
+/// Group operation id of scalar multiplication.
+pub const MUL: u64 = 2;
+
+/// Maximum number of points accepted by a multiscalar multiplication.
+pub const MAX_MULTISCALAR_POINTS: usize = 512;
+
+/// A scalar in canonical little-endian encoding.
+#[repr(transparent)]
+#[derive(Clone, Copy, Debug, Default, Eq, PartialEq)]
+pub struct PodScalar(pub [u8; 32]);
+
+#[cfg(all(not(target_os = "solana"), feature = "curve25519"))]
+impl PodScalar {
+    /// Decode the scalar, which must be in canonical form.
+    #[inline]
+    pub(crate) fn decode(&self) -> Option<curve25519_dalek::Scalar> {
+        curve25519_dalek::Scalar::from_canonical_bytes(self.0).into()
+    }
+}
+
+/// Validate a point of the curve `curve_id`.
+#[cfg(target_os = "solana")]
+#[inline]
+fn validate_point(curve_id: u64, point: &[u8; 32]) -> bool {
+    let mut result = 0u8;
+    // SAFETY: `point` is 32 bytes long; the syscall does not write to `result`.
+    let status =
+        unsafe { crate::syscalls::sol_curve_validate_point(curve_id, point.as_ptr(), &mut result) };
+    status == crate::SUCCESS
+}
+
+/// Apply the group operation `op` on two elements of the curve `curve_id`.
+#[cfg(target_os = "solana")]
+#[inline]
+fn group_op(curve_id: u64, op: u64, left: &[u8; 32], right: &[u8; 32]) -> Option<[u8; 32]> {
+    let mut point = core::mem::MaybeUninit::<[u8; 32]>::uninit();
+    // SAFETY: Inputs and result are 32 bytes long.
+    let result = unsafe {
+        crate::syscalls::sol_curve_group_op(
+            curve_id,
+            op,
+            left.as_ptr(),
+            right.as_ptr(),
+            point.as_mut_ptr() as *mut u8,
+        )
+    };
+
+    match result {
+        // SAFETY: The syscall has initialized the point.
+        crate::SUCCESS => Some(unsafe { point.assume_init() }),
+        _ => None,
+    }
+}
+
+/// Compute the multiscalar multiplication of `scalars` and `points` on the
+/// curve `curve_id`.
+///
+/// # Safety
+///
+/// `points` must point to `scalars.len()` 32-byte encoded points.
+#[cfg(target_os = "solana")]
+#[inline]
+unsafe fn multiscalar_mul(
+    curve_id: u64,
+    scalars: &[PodScalar],
+    points: *const u8,
+) -> Option<[u8; 32]> {
+    let mut point = core::mem::MaybeUninit::<[u8; 32]>::uninit();
+    let result = crate::syscalls::sol_curve_multiscalar_mul(
+        curve_id,
+        scalars.as_ptr() as *const u8,
+        points,
+        scalars.len() as u64,
+        point.as_mut_ptr() as *mut u8,
+    );
+
+    match result {
+        // SAFETY: The syscall has initialized the point.
+        crate::SUCCESS => Some(point.assume_init()),
+        _ => None,
+    }
+}
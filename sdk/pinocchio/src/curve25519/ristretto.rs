@@ -0,0 +1,243 @@
+//! Group operations on Ristretto points.
+
+use super::{PodScalar, MAX_MULTISCALAR_POINTS};
+#[cfg(target_os = "solana")]
+use super::{ADD, CURVE25519_RISTRETTO, MUL, SUB};
+
+/// A Ristretto point in compressed (32-byte) encoding.
+#[repr(transparent)]
+#[derive(Clone, Copy, Debug, Default, Eq, PartialEq)]
+pub struct PodRistrettoPoint(pub [u8; 32]);
+
+#[cfg(all(not(target_os = "solana"), feature = "curve25519"))]
+impl PodRistrettoPoint {
+    /// Decompress the point.
+    #[inline]
+    fn decode(&self) -> Option<curve25519_dalek::RistrettoPoint> {
+        curve25519_dalek::ristretto::CompressedRistretto(self.0).decompress()
+    }
+
+    /// Compress the point.
+    #[inline]
+    fn encode(point: &curve25519_dalek::RistrettoPoint) -> Self {
+        Self(point.compress().to_bytes())
+    }
+}
+
+/// Check whether `point` is a valid Ristretto point.
+///
+/// # Panics
+///
+/// On non-`solana` targets, panics if the `curve25519` feature is not enabled.
+#[inline]
+pub fn validate(point: &PodRistrettoPoint) -> bool {
+    #[cfg(target_os = "solana")]
+    {
+        super::validate_point(CURVE25519_RISTRETTO, &point.0)
+    }
+
+    #[cfg(all(not(target_os = "solana"), feature = "curve25519"))]
+    {
+        point.decode().is_some()
+    }
+
+    #[cfg(all(not(target_os = "solana"), not(feature = "curve25519")))]
+    {
+        core::hint::black_box(point);
+        panic!("ristretto::validate requires the `curve25519` feature on non-solana targets")
+    }
+}
+
+/// Add two Ristretto points.
+///
+/// Returns `None` if any of the points is invalid.
+///
+/// # Panics
+///
+/// On non-`solana` targets, panics if the `curve25519` feature is not enabled.
+#[inline]
+pub fn add(left: &PodRistrettoPoint, right: &PodRistrettoPoint) -> Option<PodRistrettoPoint> {
+    #[cfg(target_os = "solana")]
+    {
+        super::group_op(CURVE25519_RISTRETTO, ADD, &left.0, &right.0).map(PodRistrettoPoint)
+    }
+
+    #[cfg(all(not(target_os = "solana"), feature = "curve25519"))]
+    {
+        Some(PodRistrettoPoint::encode(
+            &(left.decode()? + right.decode()?),
+        ))
+    }
+
+    #[cfg(all(not(target_os = "solana"), not(feature = "curve25519")))]
+    {
+        core::hint::black_box((left, right));
+        panic!("ristretto::add requires the `curve25519` feature on non-solana targets")
+    }
+}
+
+/// Subtract the Ristretto point `right` from `left`.
+///
+/// Returns `None` if any of the points is invalid.
+///
+/// # Panics
+///
+/// On non-`solana` targets, panics if the `curve25519` feature is not enabled.
+#[inline]
+pub fn subtract(left: &PodRistrettoPoint, right: &PodRistrettoPoint) -> Option<PodRistrettoPoint> {
+    #[cfg(target_os = "solana")]
+    {
+        super::group_op(CURVE25519_RISTRETTO, SUB, &left.0, &right.0).map(PodRistrettoPoint)
+    }
+
+    #[cfg(all(not(target_os = "solana"), feature = "curve25519"))]
+    {
+        Some(PodRistrettoPoint::encode(
+            &(left.decode()? - right.decode()?),
+        ))
+    }
+
+    #[cfg(all(not(target_os = "solana"), not(feature = "curve25519")))]
+    {
+        core::hint::black_box((left, right));
+        panic!("ristretto::subtract requires the `curve25519` feature on non-solana targets")
+    }
+}
+
+/// Multiply the Ristretto point `point` by `scalar`.
+///
+/// Returns `None` if the point is invalid or the scalar is not canonical.
+///
+/// # Panics
+///
+/// On non-`solana` targets, panics if the `curve25519` feature is not enabled.
+#[inline]
+pub fn multiply(scalar: &PodScalar, point: &PodRistrettoPoint) -> Option<PodRistrettoPoint> {
+    #[cfg(target_os = "solana")]
+    {
+        super::group_op(CURVE25519_RISTRETTO, MUL, &scalar.0, &point.0).map(PodRistrettoPoint)
+    }
+
+    #[cfg(all(not(target_os = "solana"), feature = "curve25519"))]
+    {
+        Some(PodRistrettoPoint::encode(
+            &(scalar.decode()? * point.decode()?),
+        ))
+    }
+
+    #[cfg(all(not(target_os = "solana"), not(feature = "curve25519")))]
+    {
+        core::hint::black_box((scalar, point));
+        panic!("ristretto::multiply requires the `curve25519` feature on non-solana targets")
+    }
+}
+
+/// Compute the sum of each Ristretto point in `points` multiplied by the scalar
+/// at the same position in `scalars`.
+///
+/// Returns `None` if `scalars` and `points` have different lengths, there are
+/// more than [`MAX_MULTISCALAR_POINTS`] points, any of the points is invalid or
+/// any of the scalars is not canonical.
+///
+/// # Panics
+///
+/// On non-`solana` targets, panics if the `curve25519` feature is not enabled.
+#[inline]
+pub fn multiscalar_multiply(
+    scalars: &[PodScalar],
+    points: &[PodRistrettoPoint],
+) -> Option<PodRistrettoPoint> {
+    if scalars.len() != points.len() || points.len() > MAX_MULTISCALAR_POINTS {
+        return None;
+    }
+
+    #[cfg(target_os = "solana")]
+    {
+        // SAFETY: `points` has the same length as `scalars` and `PodRistrettoPoint`
+        // is a transparent wrapper of the 32-byte encoding.
+        unsafe {
+            super::multiscalar_mul(CURVE25519_RISTRETTO, scalars, points.as_ptr() as *const u8)
+        }
+        .map(PodRistrettoPoint)
+    }
+
+    #[cfg(all(not(target_os = "solana"), feature = "curve25519"))]
+    {
+        use curve25519_dalek::traits::Identity;
+
+        let result = scalars.iter().zip(points).try_fold(
+            curve25519_dalek::RistrettoPoint::identity(),
+            |sum, (scalar, point)| Some(sum + scalar.decode()? * point.decode()?),
+        )?;
+        Some(PodRistrettoPoint::encode(&result))
+    }
+
+    #[cfg(all(not(target_os = "solana"), not(feature = "curve25519")))]
+    {
+        core::hint::black_box((scalars, points));
+        panic!("ristretto::multiscalar_multiply requires the `curve25519` feature on non-solana targets")
+    }
+}
+
+#[cfg(all(test, feature = "curve25519"))]
+mod tests {
+    use super::*;
+    use curve25519_dalek::{constants::RISTRETTO_BASEPOINT_POINT as G, traits::Identity, Scalar};
+
+    #[test]
+    fn test_validate() {
+        assert!(validate(&PodRistrettoPoint::encode(&G)));
+
+        let invalid = PodRistrettoPoint([
+            120, 140, 152, 233, 41, 227, 203, 27, 87, 115, 25, 251, 219, 5, 84, 148, 117, 38, 84,
+            60, 87, 144, 161, 146, 42, 34, 91, 155, 158, 189, 121, 79,
+        ]);
+        assert!(!validate(&invalid));
+        assert_eq!(add(&invalid, &PodRistrettoPoint::encode(&G)), None);
+    }
+
+    #[test]
+    fn test_group_operations() {
+        let identity = PodRistrettoPoint::encode(&curve25519_dalek::RistrettoPoint::identity());
+        let a = Scalar::from(7u64);
+        let b = Scalar::from(11u64);
+        let point_a = PodRistrettoPoint::encode(&(a * G));
+        let point_b = PodRistrettoPoint::encode(&(b * G));
+
+        assert_eq!(add(&point_a, &identity), Some(point_a));
+        assert_eq!(subtract(&point_a, &identity), Some(point_a));
+        assert_eq!(
+            add(&point_a, &point_b),
+            Some(PodRistrettoPoint::encode(&((a + b) * G)))
+        );
+        assert_eq!(
+            subtract(&point_b, &point_a),
+            Some(PodRistrettoPoint::encode(&((b - a) * G)))
+        );
+        assert_eq!(
+            multiply(&PodScalar(b.to_bytes()), &point_a),
+            Some(PodRistrettoPoint::encode(&((a * b) * G)))
+        );
+
+        // non-canonical scalar
+        assert_eq!(multiply(&PodScalar([0xff; 32]), &point_a), None);
+    }
+
+    #[test]
+    fn test_multiscalar_multiply() {
+        let scalars = [
+            PodScalar(Scalar::from(3u64).to_bytes()),
+            PodScalar(Scalar::from(5u64).to_bytes()),
+        ];
+        let points = [
+            PodRistrettoPoint::encode(&G),
+            PodRistrettoPoint::encode(&(Scalar::from(2u64) * G)),
+        ];
+
+        assert_eq!(
+            multiscalar_multiply(&scalars, &points),
+            Some(PodRistrettoPoint::encode(&(Scalar::from(13u64) * G)))
+        );
+        assert_eq!(multiscalar_multiply(&scalars, &points[..1]), None);
+    }
+}
@@ -4,6 +4,9 @@
 pub mod lazy;
 pub use lazy::{InstructionContext, MaybeAccount};
 
+#[cfg(all(feature = "std", not(target_os = "solana")))]
+pub mod serialize;
+
 #[cfg(target_os = "solana")]
 pub use alloc::BumpAllocator;
 
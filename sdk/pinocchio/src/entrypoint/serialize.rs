@@ -0,0 +1,407 @@
+//! Host-side serialization of the program input.
+//!
+//! The SVM loader serializes the accounts, instruction data and program id of an
+//! instruction into a single byte array before calling the program entrypoint. This
+//! module builds the same (aligned) byte layout on the host, so the input can be
+//! passed directly to [`deserialize`](super::deserialize) or
+//! [`InstructionContext::new`](super::InstructionContext::new) from `cargo test`.
+//!
+//! # Example
+//!
+//! ```
+//! use pinocchio::entrypoint::{
+//!     deserialize,
+//!     serialize::{InputAccount, InputBuilder},
+//! };
+//!
+//! let program_id = [1u8; 32];
+//!
+//! let mut input = InputBuilder::new(program_id)
+//!     .account(InputAccount::new([2u8; 32], 1_000_000, program_id).signer().writable())
+//!     .duplicate(0)
+//!     .instruction_data(&[1, 2, 3])
+//!     .build();
+//!
+//! const UNINIT: core::mem::MaybeUninit<pinocchio::account_info::AccountInfo> =
+//!     core::mem::MaybeUninit::uninit();
+//! let mut accounts = [UNINIT; 2];
+//!
+//! let (id, count, data) = unsafe { deserialize::<2>(input.as_mut_ptr(), &mut accounts) };
+//!
+//! assert_eq!(id, &program_id);
+//! assert_eq!(count, 2);
+//! assert_eq!(data, &[1, 2, 3]);
+//! ```
+
+use std::vec::Vec;
+
+use crate::{
+    account_info::{Account, MAX_PERMITTED_DATA_INCREASE},
+    pubkey::Pubkey,
+    BPF_ALIGN_OF_U128, NON_DUP_MARKER,
+};
+
+/// Description of a (non-duplicated) account to serialize.
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct InputAccount {
+    /// Public key of the account.
+    pub key: Pubkey,
+
+    /// Program that owns the account.
+    pub owner: Pubkey,
+
+    /// The lamports in the account.
+    pub lamports: u64,
+
+    /// Data held in the account.
+    pub data: Vec<u8>,
+
+    /// Indicates whether the transaction was signed by this account.
+    pub is_signer: bool,
+
+    /// Indicates whether the account is writable.
+    pub is_writable: bool,
+
+    /// Indicates whether this account represents a program.
+    pub executable: bool,
+
+    /// The epoch at which this account will next owe rent.
+    pub rent_epoch: u64,
+}
+
+impl InputAccount {
+    /// Creates a new readonly, non-signer `InputAccount` without data.
+    pub fn new(key: Pubkey, lamports: u64, owner: Pubkey) -> Self {
+        Self {
+            key,
+            owner,
+            lamports,
+            ..Self::default()
+        }
+    }
+
+    /// Sets the data of the account.
+    pub fn data(mut self, data: &[u8]) -> Self {
+        self.data = data.to_vec();
+        self
+    }
+
+    /// Marks the account as a signer.
+    pub fn signer(mut self) -> Self {
+        self.is_signer = true;
+        self
+    }
+
+    /// Marks the account as writable.
+    pub fn writable(mut self) -> Self {
+        self.is_writable = true;
+        self
+    }
+
+    /// Marks the account as executable.
+    pub fn executable(mut self) -> Self {
+        self.executable = true;
+        self
+    }
+}
+
+/// An entry of the serialized account list.
+#[derive(Clone, Debug)]
+enum InputEntry {
+    /// An account that is serialized in full.
+    Account(InputAccount),
+
+    /// The index of the original account that is duplicated.
+    Duplicated(u8),
+}
+
+/// Builder for the program input byte array.
+///
+/// Accounts are serialized in the order they are added, which is the order the
+/// program receives them.
+#[derive(Clone, Debug)]
+pub struct InputBuilder {
+    /// Accounts of the instruction.
+    accounts: Vec<InputEntry>,
+
+    /// Data of the instruction.
+    instruction_data: Vec<u8>,
+
+    /// Program being invoked.
+    program_id: Pubkey,
+}
+
+impl InputBuilder {
+    /// Creates a new `InputBuilder` for an instruction of the given program.
+    pub fn new(program_id: Pubkey) -> Self {
+        Self {
+            accounts: Vec::new(),
+            instruction_data: Vec::new(),
+            program_id,
+        }
+    }
+
+    /// Adds an account to the input.
+    pub fn account(mut self, account: InputAccount) -> Self {
+        self.accounts.push(InputEntry::Account(account));
+        self
+    }
+
+    /// Adds a duplicate of the account at position `index`.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `index` does not refer to a previously added account or if it
+    /// refers to another duplicate &mdash; the loader always references the first
+    /// occurrence of an account.
+    pub fn duplicate(mut self, index: u8) -> Self {
+        match self.accounts.get(index as usize) {
+            Some(InputEntry::Account(_)) => self.accounts.push(InputEntry::Duplicated(index)),
+            Some(InputEntry::Duplicated(_)) => {
+                panic!("duplicate must reference a non-duplicated account")
+            }
+            None => panic!("duplicate index out of bounds"),
+        }
+        self
+    }
+
+    /// Sets the instruction data.
+    pub fn instruction_data(mut self, data: &[u8]) -> Self {
+        self.instruction_data = data.to_vec();
+        self
+    }
+
+    /// Serializes the input using the loader (aligned) layout.
+    pub fn build(&self) -> Input {
+        let mut bytes = Vec::with_capacity(self.serialized_len());
+
+        bytes.extend_from_slice(&(self.accounts.len() as u64).to_le_bytes());
+
+        for entry in &self.accounts {
+            match entry {
+                InputEntry::Account(account) => {
+                    bytes.push(NON_DUP_MARKER);
+                    bytes.push(account.is_signer as u8);
+                    bytes.push(account.is_writable as u8);
+                    bytes.push(account.executable as u8);
+                    // padding (original data length)
+                    bytes.extend_from_slice(&[0; 4]);
+                    bytes.extend_from_slice(&account.key);
+                    bytes.extend_from_slice(&account.owner);
+                    bytes.extend_from_slice(&account.lamports.to_le_bytes());
+                    bytes.extend_from_slice(&(account.data.len() as u64).to_le_bytes());
+                    bytes.extend_from_slice(&account.data);
+                    // space for realloc and alignment padding
+                    let padding = MAX_PERMITTED_DATA_INCREASE + align_padding(bytes.len());
+                    bytes.resize(bytes.len() + padding, 0);
+                    bytes.extend_from_slice(&account.rent_epoch.to_le_bytes());
+                }
+                InputEntry::Duplicated(index) => {
+                    bytes.push(*index);
+                    // padding to the next 8-byte boundary
+                    bytes.extend_from_slice(&[0; 7]);
+                }
+            }
+        }
+
+        bytes.extend_from_slice(&(self.instruction_data.len() as u64).to_le_bytes());
+        bytes.extend_from_slice(&self.instruction_data);
+        bytes.extend_from_slice(&self.program_id);
+
+        Input::from_bytes(&bytes)
+    }
+
+    /// Returns the length of the serialized input.
+    fn serialized_len(&self) -> usize {
+        let accounts = self
+            .accounts
+            .iter()
+            .map(|entry| match entry {
+                InputEntry::Account(account) => {
+                    let len = core::mem::size_of::<Account>() + account.data.len();
+                    len + MAX_PERMITTED_DATA_INCREASE
+                        + align_padding(len)
+                        + core::mem::size_of::<u64>()
+                }
+                InputEntry::Duplicated(_) => core::mem::size_of::<u64>(),
+            })
+            .sum::<usize>();
+
+        core::mem::size_of::<u64>() * 2
+            + accounts
+            + self.instruction_data.len()
+            + core::mem::size_of::<Pubkey>()
+    }
+}
+
+/// Returns the number of bytes required to align `len` to [`BPF_ALIGN_OF_U128`].
+///
+/// This works because `MAX_PERMITTED_DATA_INCREASE` is a multiple of the alignment.
+#[inline(always)]
+fn align_padding(len: usize) -> usize {
+    (BPF_ALIGN_OF_U128 - (len % BPF_ALIGN_OF_U128)) % BPF_ALIGN_OF_U128
+}
+
+/// Serialized program input.
+///
+/// The bytes are stored in an 8-byte aligned buffer, matching the alignment of the
+/// input region provided by the runtime.
+pub struct Input {
+    /// Aligned storage for the serialized bytes.
+    buffer: Vec<u64>,
+
+    /// Number of serialized bytes.
+    len: usize,
+}
+
+impl Input {
+    /// Copies the serialized `bytes` into an aligned buffer.
+    fn from_bytes(bytes: &[u8]) -> Self {
+        let mut buffer = std::vec![0u64; bytes.len().div_ceil(core::mem::size_of::<u64>())];
+        // SAFETY: `buffer` has at least `bytes.len()` bytes and does not overlap `bytes`.
+        unsafe {
+            core::ptr::copy_nonoverlapping(
+                bytes.as_ptr(),
+                buffer.as_mut_ptr() as *mut u8,
+                bytes.len(),
+            );
+        }
+
+        Self {
+            buffer,
+            len: bytes.len(),
+        }
+    }
+
+    /// Returns a mutable pointer to the start of the input.
+    ///
+    /// This is the pointer the runtime passes to the program entrypoint. Any
+    /// `AccountInfo` created from it is only valid while this `Input` is alive.
+    pub fn as_mut_ptr(&mut self) -> *mut u8 {
+        self.buffer.as_mut_ptr() as *mut u8
+    }
+
+    /// Returns the serialized bytes.
+    pub fn as_slice(&self) -> &[u8] {
+        // SAFETY: `buffer` holds at least `len` initialized bytes.
+        unsafe { core::slice::from_raw_parts(self.buffer.as_ptr() as *const u8, self.len) }
+    }
+
+    /// Returns the number of serialized bytes.
+    pub fn len(&self) -> usize {
+        self.len
+    }
+
+    /// Indicates whether the input is empty.
+    ///
+    /// A serialized input is never empty since it always contains the number of
+    /// accounts, instruction data length and program id.
+    pub fn is_empty(&self) -> bool {
+        self.len == 0
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use core::mem::MaybeUninit;
+
+    use super::*;
+    use crate::{
+        account_info::AccountInfo,
+        entrypoint::{deserialize, InstructionContext, MaybeAccount},
+    };
+
+    fn input() -> Input {
+        InputBuilder::new([9; 32])
+            .account(
+                InputAccount::new([1; 32], 100, [9; 32])
+                    .data(&[1, 2, 3])
+                    .signer()
+                    .writable(),
+            )
+            .account(InputAccount::new([2; 32], 200, [0; 32]).executable())
+            .duplicate(0)
+            .instruction_data(&[4, 5, 6, 7])
+            .build()
+    }
+
+    #[test]
+    fn test_deserialize() {
+        let mut input = input();
+
+        const UNINIT: MaybeUninit<AccountInfo> = MaybeUninit::<AccountInfo>::uninit();
+        let mut accounts = [UNINIT; 3];
+
+        let (program_id, count, instruction_data) =
+            unsafe { deserialize::<3>(input.as_mut_ptr(), &mut accounts) };
+
+        assert_eq!(program_id, &[9; 32]);
+        assert_eq!(count, 3);
+        assert_eq!(instruction_data, &[4, 5, 6, 7]);
+
+        let accounts: &[AccountInfo] =
+            unsafe { core::slice::from_raw_parts(accounts.as_ptr() as _, count) };
+
+        assert_eq!(accounts[0].key(), &[1; 32]);
+        assert_eq!(accounts[0].lamports(), 100);
+        assert_eq!(&*accounts[0].try_borrow_data().unwrap(), &[1, 2, 3]);
+        assert!(accounts[0].is_signer());
+        assert!(accounts[0].is_writable());
+        assert!(!accounts[0].executable());
+        assert!(accounts[0].is_owned_by(&[9; 32]));
+
+        assert_eq!(accounts[1].key(), &[2; 32]);
+        assert_eq!(accounts[1].lamports(), 200);
+        assert!(accounts[1].data_is_empty());
+        assert!(!accounts[1].is_signer());
+        assert!(!accounts[1].is_writable());
+        assert!(accounts[1].executable());
+
+        assert!(accounts[2] == accounts[0]);
+
+        // realloc uses the space reserved after the account data.
+        accounts[0]
+            .realloc(3 + MAX_PERMITTED_DATA_INCREASE, true)
+            .unwrap();
+        assert_eq!(accounts[2].data_len(), 3 + MAX_PERMITTED_DATA_INCREASE);
+    }
+
+    #[test]
+    fn test_instruction_context() {
+        let mut input = input();
+        let mut context = InstructionContext::new(input.as_mut_ptr());
+
+        assert_eq!(context.available(), 3);
+
+        let MaybeAccount::Account(first) = context.next_account().unwrap() else {
+            panic!("expected account");
+        };
+        assert_eq!(first.key(), &[1; 32]);
+
+        let MaybeAccount::Account(second) = context.next_account().unwrap() else {
+            panic!("expected account");
+        };
+        assert_eq!(second.key(), &[2; 32]);
+
+        assert!(matches!(
+            context.next_account().unwrap(),
+            MaybeAccount::Duplicated(0)
+        ));
+
+        assert_eq!(context.instruction_data().unwrap(), &[4, 5, 6, 7]);
+        assert_eq!(context.program_id().unwrap(), &[9; 32]);
+    }
+
+    #[test]
+    fn test_serialized_len() {
+        let builder = InputBuilder::new([0; 32])
+            .account(InputAccount::new([1; 32], 0, [0; 32]).data(&[0; 5]))
+            .duplicate(0);
+        let input = builder.build();
+
+        assert_eq!(input.len(), builder.serialized_len());
+        // the duplicate marker follows the first account
+        assert_eq!(input.as_slice()[8 + 10_344], 0);
+        assert_eq!(input.as_slice()[8], NON_DUP_MARKER);
+    }
+}
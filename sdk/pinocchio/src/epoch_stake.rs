@@ -0,0 +1,112 @@
+//! Stake delegated to vote accounts in the current epoch.
+//!
+//! On non-`solana` targets there is no runtime to provide the epoch stakes.
+//! When the `std` feature is enabled, the values returned on the current
+//! thread are set with [`set_epoch_stake_for_vote_account`] and
+//! [`set_epoch_total_stake`], and cleared with [`clear_epoch_stakes`];
+//! otherwise, all stakes are `0`.
+
+use crate::pubkey::Pubkey;
+
+#[cfg(all(not(target_os = "solana"), feature = "std"))]
+std::thread_local! {
+    static VOTE_ACCOUNT_STAKES: core::cell::RefCell<std::collections::HashMap<Pubkey, u64>> =
+        core::cell::RefCell::new(std::collections::HashMap::new());
+
+    static TOTAL_STAKE: core::cell::Cell<u64> = const { core::cell::Cell::new(0) };
+}
+
+/// Get the stake delegated to the vote account `vote_address` in the current
+/// epoch, in lamports.
+///
+/// Returns `0` if `vote_address` is not a vote account with delegated stake.
+#[inline]
+pub fn get_epoch_stake_for_vote_account(vote_address: &Pubkey) -> u64 {
+    #[cfg(target_os = "solana")]
+    unsafe {
+        crate::syscalls::sol_get_epoch_stake(vote_address as *const _ as *const u8)
+    }
+
+    #[cfg(all(not(target_os = "solana"), feature = "std"))]
+    {
+        VOTE_ACCOUNT_STAKES.with(|stakes| stakes.borrow().get(vote_address).copied().unwrap_or(0))
+    }
+
+    #[cfg(all(not(target_os = "solana"), not(feature = "std")))]
+    {
+        core::hint::black_box(vote_address);
+        0
+    }
+}
+
+/// Get the total stake delegated in the current epoch, in lamports.
+#[inline]
+pub fn get_epoch_total_stake() -> u64 {
+    #[cfg(target_os = "solana")]
+    unsafe {
+        // A null vote address requests the total stake.
+        crate::syscalls::sol_get_epoch_stake(core::ptr::null())
+    }
+
+    #[cfg(all(not(target_os = "solana"), feature = "std"))]
+    {
+        TOTAL_STAKE.with(|total_stake| total_stake.get())
+    }
+
+    #[cfg(all(not(target_os = "solana"), not(feature = "std")))]
+    {
+        0
+    }
+}
+
+/// Set the value returned by [`get_epoch_stake_for_vote_account`] for
+/// `vote_address` on the current thread.
+///
+/// This is only available on non-`solana` targets. The total stake is not
+/// updated; it is set with [`set_epoch_total_stake`].
+#[cfg(all(not(target_os = "solana"), feature = "std"))]
+pub fn set_epoch_stake_for_vote_account(vote_address: Pubkey, stake: u64) {
+    VOTE_ACCOUNT_STAKES.with(|stakes| stakes.borrow_mut().insert(vote_address, stake));
+}
+
+/// Set the value returned by [`get_epoch_total_stake`] on the current thread.
+///
+/// This is only available on non-`solana` targets.
+#[cfg(all(not(target_os = "solana"), feature = "std"))]
+pub fn set_epoch_total_stake(stake: u64) {
+    TOTAL_STAKE.with(|total_stake| total_stake.set(stake));
+}
+
+/// Remove the stakes set on the current thread, so that all stakes are `0`.
+///
+/// This is only available on non-`solana` targets.
+#[cfg(all(not(target_os = "solana"), feature = "std"))]
+pub fn clear_epoch_stakes() {
+    VOTE_ACCOUNT_STAKES.with(|stakes| stakes.borrow_mut().clear());
+    TOTAL_STAKE.with(|total_stake| total_stake.set(0));
+}
+
+#[cfg(all(test, feature = "std"))]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_epoch_stake() {
+        let vote_address = [1; 32];
+
+        assert_eq!(get_epoch_stake_for_vote_account(&vote_address), 0);
+        assert_eq!(get_epoch_total_stake(), 0);
+
+        set_epoch_stake_for_vote_account(vote_address, 500);
+        set_epoch_total_stake(2_000);
+
+        assert_eq!(get_epoch_stake_for_vote_account(&vote_address), 500);
+        assert_eq!(get_epoch_stake_for_vote_account(&[2; 32]), 0);
+        assert_eq!(get_epoch_total_stake(), 2_000);
+
+        clear_epoch_stakes();
+
+        assert_eq!(get_epoch_stake_for_vote_account(&vote_address), 0);
+        assert_eq!(get_epoch_total_stake(), 0);
+    }
+}
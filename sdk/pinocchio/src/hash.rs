@@ -0,0 +1,304 @@
+//! Hashing functions backed by the runtime syscalls.
+//!
+//! The functions in this module hash a list of byte slices as if they were
+//! concatenated, which is the form expected by the hashing syscalls.
+//!
+//! On non-`solana` targets, the hashes are computed in-process when the `hash`
+//! feature is enabled.
+
+use core::{marker::PhantomData, mem::MaybeUninit};
+
+use crate::program_error::ProgramError;
+
+/// Number of bytes in a hash.
+pub const HASH_BYTES: usize = 32;
+
+/// A hash value.
+pub type Hash = [u8; HASH_BYTES];
+
+/// Compute the SHA-256 hash of the concatenation of `vals`.
+///
+/// # Panics
+///
+/// On non-`solana` targets, panics if the `hash` feature is not enabled.
+#[inline]
+pub fn sha256(vals: &[&[u8]]) -> Hash {
+    #[cfg(target_os = "solana")]
+    {
+        let mut hash = MaybeUninit::<Hash>::uninit();
+        // SAFETY: `hash` has space for the 32 bytes written by the syscall.
+        unsafe {
+            crate::syscalls::sol_sha256(
+                vals as *const _ as *const u8,
+                vals.len() as u64,
+                hash.as_mut_ptr() as *mut u8,
+            );
+            hash.assume_init()
+        }
+    }
+
+    #[cfg(all(not(target_os = "solana"), feature = "hash"))]
+    {
+        use sha2::{Digest, Sha256};
+
+        #[cfg(feature = "testing")]
+        crate::testing::compute_budget::consume_hash(vals);
+
+        let mut hasher = Sha256::new();
+        vals.iter().for_each(|val| hasher.update(val));
+        hasher.finalize().into()
+    }
+
+    #[cfg(all(not(target_os = "solana"), not(feature = "hash")))]
+    {
+        core::hint::black_box(vals);
+        panic!("sha256 requires the `hash` feature on non-solana targets")
+    }
+}
+
+/// Compute the Keccak-256 hash of the concatenation of `vals`.
+///
+/// # Panics
+///
+/// On non-`solana` targets, panics if the `hash` feature is not enabled.
+#[inline]
+pub fn keccak256(vals: &[&[u8]]) -> Hash {
+    #[cfg(target_os = "solana")]
+    {
+        let mut hash = MaybeUninit::<Hash>::uninit();
+        // SAFETY: `hash` has space for the 32 bytes written by the syscall.
+        unsafe {
+            crate::syscalls::sol_keccak256(
+                vals as *const _ as *const u8,
+                vals.len() as u64,
+                hash.as_mut_ptr() as *mut u8,
+            );
+            hash.assume_init()
+        }
+    }
+
+    #[cfg(all(not(target_os = "solana"), feature = "hash"))]
+    {
+        use sha3::{Digest, Keccak256};
+
+        #[cfg(feature = "testing")]
+        crate::testing::compute_budget::consume_hash(vals);
+
+        let mut hasher = Keccak256::new();
+        vals.iter().for_each(|val| hasher.update(val));
+        hasher.finalize().into()
+    }
+
+    #[cfg(all(not(target_os = "solana"), not(feature = "hash")))]
+    {
+        core::hint::black_box(vals);
+        panic!("keccak256 requires the `hash` feature on non-solana targets")
+    }
+}
+
+/// Compute the BLAKE3 hash of the concatenation of `vals`.
+///
+/// # Panics
+///
+/// On non-`solana` targets, panics if the `hash` feature is not enabled.
+#[inline]
+pub fn blake3(vals: &[&[u8]]) -> Hash {
+    #[cfg(target_os = "solana")]
+    {
+        let mut hash = MaybeUninit::<Hash>::uninit();
+        // SAFETY: `hash` has space for the 32 bytes written by the syscall.
+        unsafe {
+            crate::syscalls::sol_blake3(
+                vals as *const _ as *const u8,
+                vals.len() as u64,
+                hash.as_mut_ptr() as *mut u8,
+            );
+            hash.assume_init()
+        }
+    }
+
+    #[cfg(all(not(target_os = "solana"), feature = "hash"))]
+    {
+        #[cfg(feature = "testing")]
+        crate::testing::compute_budget::consume_hash(vals);
+
+        let mut hasher = ::blake3::Hasher::new();
+        vals.iter().for_each(|val| {
+            hasher.update(val);
+        });
+        hasher.finalize().into()
+    }
+
+    #[cfg(all(not(target_os = "solana"), not(feature = "hash")))]
+    {
+        core::hint::black_box(vals);
+        panic!("blake3 requires the `hash` feature on non-solana targets")
+    }
+}
+
+/// A hash function that can be used with a [`Hasher`].
+pub trait HashFunction {
+    /// Compute the hash of the concatenation of `vals`.
+    fn hashv(vals: &[&[u8]]) -> Hash;
+}
+
+/// The SHA-256 hash function.
+pub struct Sha256;
+
+impl HashFunction for Sha256 {
+    #[inline(always)]
+    fn hashv(vals: &[&[u8]]) -> Hash {
+        sha256(vals)
+    }
+}
+
+/// The Keccak-256 hash function.
+pub struct Keccak256;
+
+impl HashFunction for Keccak256 {
+    #[inline(always)]
+    fn hashv(vals: &[&[u8]]) -> Hash {
+        keccak256(vals)
+    }
+}
+
+/// The BLAKE3 hash function.
+pub struct Blake3;
+
+impl HashFunction for Blake3 {
+    #[inline(always)]
+    fn hashv(vals: &[&[u8]]) -> Hash {
+        blake3(vals)
+    }
+}
+
+/// Incremental hasher.
+///
+/// The hashing syscalls take all the input at once, so the hasher collects up to
+/// `SLICES` references to the input and computes the hash with a single syscall
+/// when [`Hasher::result`] is called. No data is copied nor allocated.
+///
+/// # Example
+///
+/// ```
+/// use pinocchio::{
+///     hash::{sha256, Hasher, Sha256},
+///     program_error::ProgramError,
+/// };
+///
+/// let mut hasher = Hasher::<Sha256>::default();
+/// hasher.hash(b"hello")?;
+/// hasher.hash(b" world")?;
+/// # #[cfg(feature = "hash")]
+/// assert_eq!(hasher.result(), sha256(&[b"hello world"]));
+/// # Ok::<(), ProgramError>(())
+/// ```
+pub struct Hasher<'a, H: HashFunction, const SLICES: usize = 16> {
+    /// References to the input.
+    vals: [MaybeUninit<&'a [u8]>; SLICES],
+
+    /// Number of slices added to the hasher.
+    len: usize,
+
+    /// The hash function to use.
+    _function: PhantomData<H>,
+}
+
+impl<H: HashFunction, const SLICES: usize> Default for Hasher<'_, H, SLICES> {
+    #[inline(always)]
+    fn default() -> Self {
+        Self {
+            vals: [const { MaybeUninit::uninit() }; SLICES],
+            len: 0,
+            _function: PhantomData,
+        }
+    }
+}
+
+impl<'a, H: HashFunction, const SLICES: usize> Hasher<'a, H, SLICES> {
+    /// Add `val` to the input of the hasher.
+    ///
+    /// # Errors
+    ///
+    /// Returns [`ProgramError::InvalidArgument`] if the hasher already holds
+    /// `SLICES` slices.
+    #[inline]
+    pub fn hash(&mut self, val: &'a [u8]) -> Result<(), ProgramError> {
+        let slot = self
+            .vals
+            .get_mut(self.len)
+            .ok_or(ProgramError::InvalidArgument)?;
+        slot.write(val);
+        self.len += 1;
+        Ok(())
+    }
+
+    /// Add each slice of `vals` to the input of the hasher.
+    ///
+    /// # Errors
+    ///
+    /// Returns [`ProgramError::InvalidArgument`] if the hasher does not have
+    /// space for all slices, in which case no slice is added.
+    #[inline]
+    pub fn hashv(&mut self, vals: &[&'a [u8]]) -> Result<(), ProgramError> {
+        if vals.len() > SLICES - self.len {
+            return Err(ProgramError::InvalidArgument);
+        }
+        vals.iter().for_each(|val| {
+            self.vals[self.len].write(val);
+            self.len += 1;
+        });
+        Ok(())
+    }
+
+    /// Compute the hash of the input.
+    #[inline]
+    pub fn result(&self) -> Hash {
+        // SAFETY: The first `len` slices have been initialized.
+        H::hashv(unsafe { core::slice::from_raw_parts(self.vals.as_ptr() as _, self.len) })
+    }
+}
+
+#[cfg(all(test, feature = "hash"))]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_hash_functions() {
+        assert_eq!(
+            sha256(&[b"hello", b" ", b"world"]),
+            [
+                0xb9, 0x4d, 0x27, 0xb9, 0x93, 0x4d, 0x3e, 0x08, 0xa5, 0x2e, 0x52, 0xd7, 0xda, 0x7d,
+                0xab, 0xfa, 0xc4, 0x84, 0xef, 0xe3, 0x7a, 0x53, 0x80, 0xee, 0x90, 0x88, 0xf7, 0xac,
+                0xe2, 0xef, 0xcd, 0xe9
+            ]
+        );
+        assert_eq!(
+            keccak256(&[]),
+            [
+                0xc5, 0xd2, 0x46, 0x01, 0x86, 0xf7, 0x23, 0x3c, 0x92, 0x7e, 0x7d, 0xb2, 0xdc, 0xc7,
+                0x03, 0xc0, 0xe5, 0x00, 0xb6, 0x53, 0xca, 0x82, 0x27, 0x3b, 0x7b, 0xfa, 0xd8, 0x04,
+                0x5d, 0x85, 0xa4, 0x70
+            ]
+        );
+        assert_eq!(
+            blake3(&[]),
+            [
+                0xaf, 0x13, 0x49, 0xb9, 0xf5, 0xf9, 0xa1, 0xa6, 0xa0, 0x40, 0x4d, 0xea, 0x36, 0xdc,
+                0xc9, 0x49, 0x9b, 0xcb, 0x25, 0xc9, 0xad, 0xc1, 0x12, 0xb7, 0xcc, 0x9a, 0x93, 0xca,
+                0xe4, 0x1f, 0x32, 0x62
+            ]
+        );
+    }
+
+    #[test]
+    fn test_hasher() {
+        let mut hasher = Hasher::<Keccak256, 3>::default();
+
+        hasher.hash(b"hello").unwrap();
+        hasher.hashv(&[b" ", b"world"]).unwrap();
+        assert_eq!(hasher.hash(b"!"), Err(ProgramError::InvalidArgument));
+
+        assert_eq!(hasher.result(), keccak256(&[b"hello world"]));
+    }
+}
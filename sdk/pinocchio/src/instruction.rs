@@ -2,7 +2,7 @@
 
 use core::{marker::PhantomData, ops::Deref};
 
-use crate::{account_info::AccountInfo, pubkey::Pubkey};
+use crate::{account_info::AccountInfo, program_error::ProgramError, pubkey::Pubkey};
 
 /// Information about a CPI instruction.
 #[derive(Debug, Clone)]
@@ -32,6 +32,257 @@ pub struct ProcessedSiblingInstruction {
     pub accounts_len: u64,
 }
 
+/// An account meta of a processed sibling instruction.
+///
+/// This struct has the memory layout as written by the
+/// `sol_get_processed_sibling_instruction` syscall.
+#[repr(C)]
+#[derive(Default, Debug, Clone, Copy, Eq, PartialEq)]
+pub struct ProcessedAccountMeta {
+    /// Public key of the account.
+    pub pubkey: Pubkey,
+
+    /// Indicates whether the account signed the instruction or not.
+    pub is_signer: bool,
+
+    /// Indicates whether the account is writable or not.
+    pub is_writable: bool,
+}
+
+impl ProcessedAccountMeta {
+    /// Convert the `ProcessedAccountMeta` to an `AccountMeta`.
+    #[inline(always)]
+    pub fn to_account_meta(&self) -> AccountMeta<'_> {
+        AccountMeta::new(&self.pubkey, self.is_writable, self.is_signer)
+    }
+}
+
+/// A processed sibling instruction, borrowing the buffers it was copied into.
+#[derive(Debug, Clone, Eq, PartialEq)]
+pub struct SiblingInstruction<'d, 'm> {
+    /// Public key of the program.
+    program_id: Pubkey,
+
+    /// Data of the instruction.
+    data: &'d [u8],
+
+    /// Accounts of the instruction.
+    accounts: &'m [ProcessedAccountMeta],
+}
+
+impl SiblingInstruction<'_, '_> {
+    /// Return the program ID of the instruction.
+    #[inline(always)]
+    pub fn program_id(&self) -> &Pubkey {
+        &self.program_id
+    }
+
+    /// Return the data of the instruction.
+    #[inline(always)]
+    pub fn data(&self) -> &[u8] {
+        self.data
+    }
+
+    /// Return the accounts of the instruction.
+    #[inline(always)]
+    pub fn accounts(&self) -> &[ProcessedAccountMeta] {
+        self.accounts
+    }
+}
+
+/// Get a processed sibling instruction.
+///
+/// Sibling instructions are the instructions processed at the same stack
+/// height as the current instruction, before it – e.g., the previous top-level
+/// instructions of the transaction or the previous instructions invoked by the
+/// same caller. The `index` is counted backwards from the most recent one, so
+/// index `0` is the instruction processed right before the current one.
+///
+/// The instruction data and accounts are copied into `data` and `accounts`,
+/// which must be large enough to hold them. Returns `None` when there is no
+/// sibling instruction at `index`.
+///
+/// # Errors
+///
+/// Returns [`ProgramError::InvalidArgument`] if `data` or `accounts` is too
+/// small to hold the instruction.
+#[inline]
+pub fn get_processed_sibling_instruction<'d, 'm>(
+    index: usize,
+    data: &'d mut [u8],
+    accounts: &'m mut [ProcessedAccountMeta],
+) -> Result<Option<SiblingInstruction<'d, 'm>>, ProgramError> {
+    #[cfg(target_os = "solana")]
+    {
+        let mut meta = ProcessedSiblingInstruction::default();
+        let mut program_id = Pubkey::default();
+
+        // The syscall only copies the instruction when the lengths in `meta`
+        // match the instruction, so the first call queries the lengths.
+        let found = unsafe {
+            crate::syscalls::sol_get_processed_sibling_instruction(
+                index as u64,
+                &mut meta,
+                &mut program_id,
+                data.as_mut_ptr(),
+                accounts.as_mut_ptr(),
+            )
+        };
+
+        if found == 0 {
+            return Ok(None);
+        }
+
+        let data_len = meta.data_len as usize;
+        let accounts_len = meta.accounts_len as usize;
+
+        if data_len > data.len() || accounts_len > accounts.len() {
+            return Err(ProgramError::InvalidArgument);
+        }
+
+        // An instruction without data and accounts was already copied.
+        if data_len > 0 || accounts_len > 0 {
+            unsafe {
+                crate::syscalls::sol_get_processed_sibling_instruction(
+                    index as u64,
+                    &mut meta,
+                    &mut program_id,
+                    data.as_mut_ptr(),
+                    accounts.as_mut_ptr(),
+                )
+            };
+        }
+
+        Ok(Some(SiblingInstruction {
+            program_id,
+            data: &data[..data_len],
+            accounts: &accounts[..accounts_len],
+        }))
+    }
+
+    #[cfg(not(target_os = "solana"))]
+    {
+        core::hint::black_box((index, data, accounts));
+        Ok(None)
+    }
+}
+
+/// A processed sibling instruction, holding its data and accounts.
+///
+/// This is the item of [`SiblingInstructions`]. The instruction is stored
+/// inline, so `DATA` and `ACCOUNTS` should be kept small to fit the stack.
+#[derive(Debug, Clone)]
+pub struct OwnedSiblingInstruction<const DATA: usize, const ACCOUNTS: usize> {
+    /// Public key of the program.
+    program_id: Pubkey,
+
+    /// Buffer holding the data of the instruction.
+    data: [u8; DATA],
+
+    /// Length of the data of the instruction.
+    data_len: usize,
+
+    /// Buffer holding the accounts of the instruction.
+    accounts: [ProcessedAccountMeta; ACCOUNTS],
+
+    /// Number of accounts of the instruction.
+    accounts_len: usize,
+}
+
+impl<const DATA: usize, const ACCOUNTS: usize> OwnedSiblingInstruction<DATA, ACCOUNTS> {
+    /// Return the program ID of the instruction.
+    #[inline(always)]
+    pub fn program_id(&self) -> &Pubkey {
+        &self.program_id
+    }
+
+    /// Return the data of the instruction.
+    #[inline(always)]
+    pub fn data(&self) -> &[u8] {
+        &self.data[..self.data_len]
+    }
+
+    /// Return the accounts of the instruction.
+    #[inline(always)]
+    pub fn accounts(&self) -> &[ProcessedAccountMeta] {
+        &self.accounts[..self.accounts_len]
+    }
+}
+
+/// Iterator over the processed sibling instructions, from the most recent one.
+///
+/// Each instruction can hold up to `DATA` bytes of data and `ACCOUNTS`
+/// accounts; larger instructions are yielded as
+/// [`ProgramError::InvalidArgument`] errors.
+#[derive(Debug, Default, Clone)]
+pub struct SiblingInstructions<const DATA: usize, const ACCOUNTS: usize> {
+    /// Index of the next instruction.
+    index: usize,
+
+    /// Indicates whether all instructions were yielded.
+    done: bool,
+}
+
+impl<const DATA: usize, const ACCOUNTS: usize> SiblingInstructions<DATA, ACCOUNTS> {
+    /// Creates a new `SiblingInstructions` iterator.
+    #[inline(always)]
+    pub fn new() -> Self {
+        Self {
+            index: 0,
+            done: false,
+        }
+    }
+}
+
+impl<const DATA: usize, const ACCOUNTS: usize> Iterator for SiblingInstructions<DATA, ACCOUNTS> {
+    type Item = Result<OwnedSiblingInstruction<DATA, ACCOUNTS>, ProgramError>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        if self.done {
+            return None;
+        }
+
+        let mut instruction = OwnedSiblingInstruction {
+            program_id: Pubkey::default(),
+            data: [0; DATA],
+            data_len: 0,
+            accounts: [ProcessedAccountMeta::default(); ACCOUNTS],
+            accounts_len: 0,
+        };
+
+        let result = get_processed_sibling_instruction(
+            self.index,
+            &mut instruction.data,
+            &mut instruction.accounts,
+        )
+        .map(|sibling| {
+            sibling.map(|sibling| {
+                (
+                    sibling.program_id,
+                    sibling.data.len(),
+                    sibling.accounts.len(),
+                )
+            })
+        });
+
+        self.index += 1;
+
+        match result {
+            Ok(Some((program_id, data_len, accounts_len))) => {
+                instruction.program_id = program_id;
+                instruction.data_len = data_len;
+                instruction.accounts_len = accounts_len;
+                Some(Ok(instruction))
+            }
+            Ok(None) => {
+                self.done = true;
+                None
+            }
+            Err(error) => Some(Err(error)),
+        }
+    }
+}
+
 /// An `Account` for CPI invocations.
 ///
 /// This struct contains the same information as an [`AccountInfo`], but has
@@ -98,6 +349,16 @@ impl<'a> From<&'a AccountInfo> for Account<'a> {
     }
 }
 
+#[cfg(all(not(target_os = "solana"), feature = "testing"))]
+impl Account<'_> {
+    /// Returns the raw account the `Account` was created from.
+    #[inline(always)]
+    pub(crate) fn raw(&self) -> *mut crate::account_info::Account {
+        // The key is at offset 8 of the raw account.
+        unsafe { (self.key as *const u8).sub(8) as *mut crate::account_info::Account }
+    }
+}
+
 /// Describes a single account read or written by a program during instruction
 /// execution.
 ///
@@ -298,3 +559,27 @@ macro_rules! seeds {
         )*]
     };
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_processed_account_meta_layout() {
+        // Same layout as the account metas written by the runtime.
+        assert_eq!(core::mem::size_of::<ProcessedAccountMeta>(), 34);
+        assert_eq!(core::mem::align_of::<ProcessedAccountMeta>(), 1);
+    }
+
+    #[test]
+    fn test_sibling_instructions() {
+        // There are no sibling instructions off-chain.
+        let mut data = [0; 8];
+        let mut accounts = [ProcessedAccountMeta::default(); 2];
+        assert_eq!(
+            get_processed_sibling_instruction(0, &mut data, &mut accounts),
+            Ok(None)
+        );
+        assert!(SiblingInstructions::<8, 2>::new().next().is_none());
+    }
+}
@@ -180,6 +180,81 @@
 //! crate. This crate provides a lightweight `log!` macro with better compute units
 //! consumption than the standard `format!` macro without requiring the `std` library.
 //!
+//! On non-`solana` targets, the `std` feature also enables the `sysvars::host`
+//! module, which sets the values returned by [`sysvars::Sysvar::get`] and the
+//! partial sysvar reads in tests (e.g., the current `Clock`).
+//!
+//! ## `curve25519` crate feature
+//!
+//! Program derived addresses are computed by the runtime through syscalls, which
+//! are not available off-chain. Enabling the `curve25519` feature allows
+//! [`pubkey::find_program_address`] and [`pubkey::create_program_address`] to
+//! derive addresses on non-`solana` targets (e.g., in tests and clients):
+//! ```ignore
+//! pinocchio = { version = "0.8.1", features = ["curve25519"] }
+//! ```
+//!
+//! The same feature also enables the group operations of the [`curve25519`]
+//! module off-chain.
+//!
+//! The feature has no effect on `solana` targets, so on-chain programs remain
+//! dependency-free.
+//!
+//! ## `hash` crate feature
+//!
+//! The functions in the [`hash`] module are backed by the runtime hashing
+//! syscalls. Enabling the `hash` feature computes the hashes in-process on
+//! non-`solana` targets:
+//! ```ignore
+//! pinocchio = { version = "0.8.1", features = ["hash"] }
+//! ```
+//!
+//! ## `secp256k1` crate feature
+//!
+//! Enabling the `secp256k1` feature allows [`secp256k1::recover`] to recover
+//! public keys on non-`solana` targets. It also enables the `hash` feature,
+//! which [`secp256k1::eth_address`] relies on:
+//! ```ignore
+//! pinocchio = { version = "0.8.1", features = ["secp256k1"] }
+//! ```
+//!
+//! ## `alt_bn128` crate feature
+//!
+//! Enabling the `alt_bn128` feature computes the operations of the [`alt_bn128`]
+//! module in-process on non-`solana` targets:
+//! ```ignore
+//! pinocchio = { version = "0.8.1", features = ["alt_bn128"] }
+//! ```
+//!
+//! ## `poseidon` crate feature
+//!
+//! Enabling the `poseidon` feature computes [`poseidon::hashv`] in-process on
+//! non-`solana` targets:
+//! ```ignore
+//! pinocchio = { version = "0.8.1", features = ["poseidon"] }
+//! ```
+//!
+//! ## `big_mod_exp` crate feature
+//!
+//! Enabling the `big_mod_exp` feature computes [`big_mod_exp::big_mod_exp`]
+//! in-process on non-`solana` targets:
+//! ```ignore
+//! pinocchio = { version = "0.8.1", features = ["big_mod_exp"] }
+//! ```
+//!
+//! ## `testing` crate feature
+//!
+//! Enabling the `testing` feature adds the [`testing`] module on non-`solana`
+//! targets. Tests register a handler per program id, and cross-program
+//! invocations are dispatched in-process to the registered programs:
+//! ```ignore
+//! pinocchio = { version = "0.8.1", features = ["testing"] }
+//! ```
+//!
+//! The syscall wrappers also charge the compute units of the corresponding
+//! syscalls, which [`testing::compute_budget`] reports per test, and the
+//! logging functions record the program logs in [`testing::logs`].
+//!
 //! ## Advanced entrypoint configuration
 //!
 //! The symbols emitted by the entrypoint macros &mdash; program entrypoint, global
@@ -224,19 +299,29 @@
 extern crate std;
 
 pub mod account_info;
+pub mod alt_bn128;
+pub mod big_mod_exp;
+pub mod compute_units;
 pub mod cpi;
+pub mod curve25519;
 pub mod entrypoint;
+pub mod epoch_stake;
+pub mod hash;
 pub mod instruction;
 pub mod log;
 pub mod memory;
+pub mod poseidon;
 #[deprecated(since = "0.8.0", note = "Use the `cpi` module instead")]
 pub mod program {
     pub use crate::cpi::*;
 }
 pub mod program_error;
 pub mod pubkey;
+pub mod secp256k1;
 pub mod syscalls;
 pub mod sysvars;
+#[cfg(all(not(target_os = "solana"), feature = "testing"))]
+pub mod testing;
 
 #[deprecated(since = "0.7.0", note = "Use the `entrypoint` module instead")]
 pub use entrypoint::lazy as lazy_entrypoint;
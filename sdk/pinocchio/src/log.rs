@@ -99,6 +99,14 @@ pub fn sol_log(message: &str) {
         crate::syscalls::sol_log_(message.as_ptr(), message.len() as u64);
     }
 
+    #[cfg(all(not(target_os = "solana"), feature = "testing"))]
+    {
+        use crate::testing::{compute_budget, logs};
+
+        compute_budget::consume(compute_budget::SYSCALL_BASE_COST.max(message.len() as u64));
+        logs::record(logs::Log::Message(message.into()));
+    }
+
     #[cfg(not(target_os = "solana"))]
     core::hint::black_box(message);
 }
@@ -111,6 +119,14 @@ pub fn sol_log_64(arg1: u64, arg2: u64, arg3: u64, arg4: u64, arg5: u64) {
         crate::syscalls::sol_log_64_(arg1, arg2, arg3, arg4, arg5);
     }
 
+    #[cfg(all(not(target_os = "solana"), feature = "testing"))]
+    {
+        use crate::testing::{compute_budget, logs};
+
+        compute_budget::consume(compute_budget::LOG_64_UNITS);
+        logs::record(logs::Log::U64([arg1, arg2, arg3, arg4, arg5]));
+    }
+
     #[cfg(not(target_os = "solana"))]
     core::hint::black_box((arg1, arg2, arg3, arg4, arg5));
 }
@@ -122,6 +138,18 @@ pub fn sol_log_data(data: &[&[u8]]) {
         crate::syscalls::sol_log_data(data as *const _ as *const u8, data.len() as u64)
     };
 
+    #[cfg(all(not(target_os = "solana"), feature = "testing"))]
+    {
+        use crate::testing::{compute_budget, logs};
+
+        compute_budget::consume(compute_budget::SYSCALL_BASE_COST);
+        compute_budget::consume(compute_budget::SYSCALL_BASE_COST * data.len() as u64);
+        compute_budget::consume(data.iter().map(|field| field.len() as u64).sum());
+        logs::record(logs::Log::Data(
+            data.iter().map(|field| field.to_vec()).collect(),
+        ));
+    }
+
     #[cfg(not(target_os = "solana"))]
     core::hint::black_box(data);
 }
@@ -164,4 +192,12 @@ pub fn sol_log_compute_units() {
     unsafe {
         crate::syscalls::sol_log_compute_units_();
     }
+
+    #[cfg(all(not(target_os = "solana"), feature = "testing"))]
+    {
+        use crate::testing::{compute_budget, logs};
+
+        compute_budget::consume(compute_budget::SYSCALL_BASE_COST);
+        logs::record(logs::Log::ComputeUnits(compute_budget::remaining()));
+    }
 }
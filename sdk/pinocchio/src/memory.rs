@@ -37,6 +37,9 @@ pub unsafe fn sol_memcpy(dst: &mut [u8], src: &[u8], n: usize) {
     #[cfg(target_os = "solana")]
     syscalls::sol_memcpy_(dst.as_mut_ptr(), src.as_ptr(), n as u64);
 
+    #[cfg(all(not(target_os = "solana"), feature = "testing"))]
+    crate::testing::compute_budget::consume_mem_op(n);
+
     #[cfg(not(target_os = "solana"))]
     core::hint::black_box((dst, src, n));
 }
@@ -65,6 +68,9 @@ pub unsafe fn sol_memmove(dst: *mut u8, src: *mut u8, n: usize) {
     #[cfg(target_os = "solana")]
     syscalls::sol_memmove_(dst, src, n as u64);
 
+    #[cfg(all(not(target_os = "solana"), feature = "testing"))]
+    crate::testing::compute_budget::consume_mem_op(n);
+
     #[cfg(not(target_os = "solana"))]
     core::hint::black_box((dst, src, n));
 }
@@ -99,6 +105,9 @@ pub unsafe fn sol_memcmp(s1: &[u8], s2: &[u8], n: usize) -> i32 {
     #[cfg(target_os = "solana")]
     syscalls::sol_memcmp_(s1.as_ptr(), s2.as_ptr(), n as u64, &mut result as *mut i32);
 
+    #[cfg(all(not(target_os = "solana"), feature = "testing"))]
+    crate::testing::compute_budget::consume_mem_op(n);
+
     #[cfg(not(target_os = "solana"))]
     core::hint::black_box((s1, s2, n, result));
 
@@ -132,6 +141,9 @@ pub unsafe fn sol_memset(s: &mut [u8], c: u8, n: usize) {
     #[cfg(target_os = "solana")]
     syscalls::sol_memset_(s.as_mut_ptr(), c, n as u64);
 
+    #[cfg(all(not(target_os = "solana"), feature = "testing"))]
+    crate::testing::compute_budget::consume_mem_op(n);
+
     #[cfg(not(target_os = "solana"))]
     core::hint::black_box((s, c, n));
 }
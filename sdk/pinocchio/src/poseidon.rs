@@ -0,0 +1,254 @@
+//! [Poseidon] hash function over the BN254 scalar field.
+//!
+//! [Poseidon]: https://eprint.iacr.org/2019/458
+//!
+//! The hash is compatible with [circomlib]'s Poseidon implementation. On
+//! non-`solana` targets, the hash is computed in-process when the `poseidon`
+//! feature is enabled.
+//!
+//! [circomlib]: https://github.com/iden3/circomlib
+
+/// Number of bytes in a Poseidon hash.
+pub const HASH_BYTES: usize = 32;
+
+/// Maximum number of inputs accepted by [`hashv`].
+pub const MAX_INPUTS: usize = 12;
+
+/// A Poseidon hash value.
+pub type PoseidonHash = [u8; HASH_BYTES];
+
+/// Parameters of the Poseidon hash function.
+#[repr(u64)]
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+pub enum Parameters {
+    /// BN254 curve scalar field, x^5 S-boxes, width `t` equal to the number
+    /// of inputs plus one, 8 full rounds and the partial rounds of circomlib.
+    Bn254X5 = 0,
+}
+
+/// Byte order of the inputs and the output of the hash function.
+#[repr(u64)]
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+pub enum Endianness {
+    /// Big-endian.
+    BigEndian = 0,
+
+    /// Little-endian.
+    LittleEndian = 1,
+}
+
+/// Errors returned by [`hashv`].
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+pub enum PoseidonSyscallError {
+    /// Invalid parameters.
+    InvalidParameters,
+
+    /// Invalid endianness.
+    InvalidEndianness,
+
+    /// Invalid number of inputs; the maximum is [`MAX_INPUTS`].
+    InvalidNumberOfInputs,
+
+    /// Input is an empty slice.
+    EmptyInput,
+
+    /// Invalid length of an input; each input must be at most 32 bytes long.
+    InvalidInputLength,
+
+    /// Failed to convert bytes into a prime field element.
+    BytesToPrimeFieldElement,
+
+    /// Input is larger than the modulus of the prime field.
+    InputLargerThanModulus,
+
+    /// Failed to convert a vector of bytes into an array.
+    VecToArray,
+
+    /// Failed to convert the number of inputs from `u64` to `u8`.
+    U64Tou8,
+
+    /// Failed to convert bytes to a big integer.
+    BytesToBigInt,
+
+    /// Invalid width; the width must be between 2 and 16.
+    InvalidWidthCircom,
+
+    /// Unexpected error.
+    Unexpected,
+}
+
+impl From<u64> for PoseidonSyscallError {
+    fn from(error: u64) -> Self {
+        match error {
+            1 => Self::InvalidParameters,
+            2 => Self::InvalidEndianness,
+            3 => Self::InvalidNumberOfInputs,
+            4 => Self::EmptyInput,
+            5 => Self::InvalidInputLength,
+            6 => Self::BytesToPrimeFieldElement,
+            7 => Self::InputLargerThanModulus,
+            8 => Self::VecToArray,
+            9 => Self::U64Tou8,
+            10 => Self::BytesToBigInt,
+            11 => Self::InvalidWidthCircom,
+            _ => Self::Unexpected,
+        }
+    }
+}
+
+impl From<PoseidonSyscallError> for u64 {
+    fn from(error: PoseidonSyscallError) -> Self {
+        match error {
+            PoseidonSyscallError::InvalidParameters => 1,
+            PoseidonSyscallError::InvalidEndianness => 2,
+            PoseidonSyscallError::InvalidNumberOfInputs => 3,
+            PoseidonSyscallError::EmptyInput => 4,
+            PoseidonSyscallError::InvalidInputLength => 5,
+            PoseidonSyscallError::BytesToPrimeFieldElement => 6,
+            PoseidonSyscallError::InputLargerThanModulus => 7,
+            PoseidonSyscallError::VecToArray => 8,
+            PoseidonSyscallError::U64Tou8 => 9,
+            PoseidonSyscallError::BytesToBigInt => 10,
+            PoseidonSyscallError::InvalidWidthCircom => 11,
+            PoseidonSyscallError::Unexpected => 12,
+        }
+    }
+}
+
+/// Compute the Poseidon hash of `vals`.
+///
+/// Each value is a field element of at most 32 bytes in the byte order given
+/// by `endianness`, which is also the byte order of the returned hash.
+///
+/// # Errors
+///
+/// Returns [`PoseidonSyscallError::InvalidNumberOfInputs`] if there are more
+/// than [`MAX_INPUTS`] values, without invoking the syscall. The other errors
+/// are reported by the hash function itself, e.g.
+/// [`PoseidonSyscallError::InputLargerThanModulus`] when a value is not a
+/// valid field element.
+///
+/// # Panics
+///
+/// On non-`solana` targets, panics if the `poseidon` feature is not enabled.
+#[inline]
+pub fn hashv(
+    parameters: Parameters,
+    endianness: Endianness,
+    vals: &[&[u8]],
+) -> Result<PoseidonHash, PoseidonSyscallError> {
+    // The runtime aborts the program, rather than returning an error, when
+    // there are too many inputs.
+    if vals.len() > MAX_INPUTS {
+        return Err(PoseidonSyscallError::InvalidNumberOfInputs);
+    }
+
+    #[cfg(target_os = "solana")]
+    {
+        let mut hash = core::mem::MaybeUninit::<PoseidonHash>::uninit();
+        let result = unsafe {
+            crate::syscalls::sol_poseidon(
+                parameters as u64,
+                endianness as u64,
+                vals as *const _ as *const u8,
+                vals.len() as u64,
+                hash.as_mut_ptr() as *mut u8,
+            )
+        };
+
+        match result {
+            // SAFETY: The syscall has initialized the hash.
+            crate::SUCCESS => Ok(unsafe { hash.assume_init() }),
+            _ => Err(result.into()),
+        }
+    }
+
+    #[cfg(all(not(target_os = "solana"), feature = "poseidon"))]
+    {
+        use light_poseidon::{Poseidon, PoseidonBytesHasher, PoseidonError};
+
+        let Parameters::Bn254X5 = parameters;
+
+        let result =
+            Poseidon::<ark_bn254::Fr>::new_circom(vals.len()).and_then(
+                |mut hasher| match endianness {
+                    Endianness::BigEndian => hasher.hash_bytes_be(vals),
+                    Endianness::LittleEndian => hasher.hash_bytes_le(vals),
+                },
+            );
+
+        result.map_err(|error| match error {
+            PoseidonError::InvalidNumberOfInputs { .. } => {
+                PoseidonSyscallError::InvalidNumberOfInputs
+            }
+            PoseidonError::EmptyInput => PoseidonSyscallError::EmptyInput,
+            PoseidonError::InvalidInputLength { .. } => PoseidonSyscallError::InvalidInputLength,
+            PoseidonError::BytesToPrimeFieldElement { .. } => {
+                PoseidonSyscallError::BytesToPrimeFieldElement
+            }
+            PoseidonError::InputLargerThanModulus => PoseidonSyscallError::InputLargerThanModulus,
+            PoseidonError::VecToArray => PoseidonSyscallError::VecToArray,
+            PoseidonError::U64Tou8 => PoseidonSyscallError::U64Tou8,
+            PoseidonError::BytesToBigInt => PoseidonSyscallError::BytesToBigInt,
+            PoseidonError::InvalidWidthCircom { .. } => PoseidonSyscallError::InvalidWidthCircom,
+        })
+    }
+
+    #[cfg(all(not(target_os = "solana"), not(feature = "poseidon")))]
+    {
+        core::hint::black_box((parameters, endianness, vals));
+        panic!("poseidon hashv requires the `poseidon` feature on non-solana targets")
+    }
+}
+
+#[cfg(all(test, feature = "poseidon"))]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_hashv() {
+        let expected = [
+            13, 84, 225, 147, 143, 138, 140, 28, 125, 235, 94, 3, 85, 242, 99, 25, 32, 123, 132,
+            254, 156, 162, 206, 27, 38, 231, 53, 200, 41, 130, 25, 144,
+        ];
+        assert_eq!(
+            hashv(
+                Parameters::Bn254X5,
+                Endianness::BigEndian,
+                &[&[1u8; 32], &[2u8; 32]]
+            ),
+            Ok(expected)
+        );
+
+        let mut reversed = expected;
+        reversed.reverse();
+        assert_eq!(
+            hashv(
+                Parameters::Bn254X5,
+                Endianness::LittleEndian,
+                &[&[1u8; 32], &[2u8; 32]]
+            ),
+            Ok(reversed)
+        );
+    }
+
+    #[test]
+    fn test_hashv_errors() {
+        assert_eq!(
+            hashv(Parameters::Bn254X5, Endianness::BigEndian, &[&[0xff; 32]]),
+            Err(PoseidonSyscallError::InputLargerThanModulus)
+        );
+        assert_eq!(
+            hashv(Parameters::Bn254X5, Endianness::BigEndian, &[&[1u8; 33]]),
+            Err(PoseidonSyscallError::InvalidInputLength)
+        );
+        assert_eq!(
+            hashv(
+                Parameters::Bn254X5,
+                Endianness::BigEndian,
+                &[&[1u8; 32] as &[u8]; MAX_INPUTS + 1]
+            ),
+            Err(PoseidonSyscallError::InvalidNumberOfInputs)
+        );
+    }
+}
@@ -11,6 +11,9 @@ pub const MAX_SEED_LEN: usize = 32;
 /// Maximum number of seeds.
 pub const MAX_SEEDS: usize = 16;
 
+/// Marker appended to the seeds when deriving a program address.
+pub const PDA_MARKER: &[u8; 21] = b"ProgramDerivedAddress";
+
 /// The address of a [Solana account][account].
 ///
 /// [account]: https://solana.com/docs/core/accounts
@@ -24,6 +27,14 @@ pub fn log(pubkey: &Pubkey) {
         crate::syscalls::sol_log_pubkey(pubkey as *const _ as *const u8)
     };
 
+    #[cfg(all(not(target_os = "solana"), feature = "testing"))]
+    {
+        use crate::testing::{compute_budget, logs};
+
+        compute_budget::consume(compute_budget::LOG_PUBKEY_UNITS);
+        logs::record(logs::Log::Pubkey(*pubkey));
+    }
+
     #[cfg(not(target_os = "solana"))]
     core::hint::black_box(pubkey);
 }
@@ -116,6 +127,9 @@ pub fn find_program_address(seeds: &[&[u8]], program_id: &Pubkey) -> (Pubkey, u8
 /// See the documentation for [`find_program_address`] for a full description.
 ///
 /// [`find_program_address`]: #find_program_address
+///
+/// On non-`solana` targets, the address is derived in-process when the `curve25519`
+/// feature is enabled; otherwise this function always returns `None`.
 #[inline]
 pub fn try_find_program_address(seeds: &[&[u8]], program_id: &Pubkey) -> Option<(Pubkey, u8)> {
     #[cfg(target_os = "solana")]
@@ -139,7 +153,31 @@ pub fn try_find_program_address(seeds: &[&[u8]], program_id: &Pubkey) -> Option<
         }
     }
 
-    #[cfg(not(target_os = "solana"))]
+    #[cfg(all(not(target_os = "solana"), feature = "curve25519"))]
+    {
+        if seeds.len() >= MAX_SEEDS {
+            return None;
+        }
+
+        let bumps: [[u8; 1]; 256] = core::array::from_fn(|i| [i as u8]);
+        let mut seeds_with_bump = [&[] as &[u8]; MAX_SEEDS];
+        seeds_with_bump[..seeds.len()].copy_from_slice(seeds);
+
+        // Same search as the runtime: bump seeds from `255` down to `1`.
+        for bump_seed in (1..=u8::MAX).rev() {
+            seeds_with_bump[seeds.len()] = &bumps[bump_seed as usize];
+
+            match create_program_address(&seeds_with_bump[..=seeds.len()], program_id) {
+                Ok(address) => return Some((address, bump_seed)),
+                Err(ProgramError::InvalidSeeds) => (),
+                Err(_) => break,
+            }
+        }
+
+        None
+    }
+
+    #[cfg(all(not(target_os = "solana"), not(feature = "curve25519")))]
     {
         core::hint::black_box((seeds, program_id));
         None
@@ -166,6 +204,10 @@ pub fn try_find_program_address(seeds: &[&[u8]], program_id: &Pubkey) -> Option<
 /// incurring the cost of the syscall.
 ///
 /// [`find_program_address`]: #find_program_address
+///
+/// # Panics
+///
+/// On non-`solana` targets, panics if the `curve25519` feature is not enabled.
 #[inline]
 pub fn create_program_address(
     seeds: &[&[u8]],
@@ -192,13 +234,61 @@ pub fn create_program_address(
         }
     }
 
-    #[cfg(not(target_os = "solana"))]
+    #[cfg(all(not(target_os = "solana"), feature = "curve25519"))]
+    {
+        #[cfg(feature = "testing")]
+        crate::testing::compute_budget::consume(
+            crate::testing::compute_budget::CREATE_PROGRAM_ADDRESS_UNITS,
+        );
+
+        derive_program_address(seeds, program_id)
+    }
+
+    #[cfg(all(not(target_os = "solana"), not(feature = "curve25519")))]
     {
         core::hint::black_box((seeds, program_id));
-        panic!("create_program_address is only available on target `solana`")
+        panic!("create_program_address requires the `curve25519` feature on non-solana targets")
     }
 }
 
+/// Derive a program address in-process.
+#[cfg(all(not(target_os = "solana"), feature = "curve25519"))]
+pub(crate) fn derive_program_address(
+    seeds: &[&[u8]],
+    program_id: &Pubkey,
+) -> Result<Pubkey, ProgramError> {
+    use sha2::{Digest, Sha256};
+
+    if seeds.len() > MAX_SEEDS || seeds.iter().any(|seed| seed.len() > MAX_SEED_LEN) {
+        return Err(ProgramError::MaxSeedLengthExceeded);
+    }
+
+    let mut hasher = Sha256::new();
+    for seed in seeds {
+        hasher.update(seed);
+    }
+    hasher.update(program_id);
+    hasher.update(PDA_MARKER);
+    let hash: [u8; PUBKEY_BYTES] = hasher.finalize().into();
+
+    if is_on_curve(&hash) {
+        return Err(ProgramError::InvalidSeeds);
+    }
+
+    Ok(hash)
+}
+
+/// Checks whether the given bytes represent a point on the ed25519 curve.
+///
+/// Program derived addresses are guaranteed to not be on the curve.
+#[cfg(all(not(target_os = "solana"), feature = "curve25519"))]
+#[inline]
+pub fn is_on_curve(bytes: &[u8; PUBKEY_BYTES]) -> bool {
+    curve25519_dalek::edwards::CompressedEdwardsY(*bytes)
+        .decompress()
+        .is_some()
+}
+
 /// Create a valid [program derived address][pda] without searching for a bump seed.
 ///
 /// [pda]: https://solana.com/docs/core/cpi#program-derived-addresses
@@ -232,3 +322,97 @@ pub fn checked_create_program_address(
 
     create_program_address(seeds, program_id)
 }
+
+#[cfg(all(test, feature = "curve25519"))]
+mod tests {
+    use super::*;
+    use five8_const::decode_32_const;
+
+    const PROGRAM_ID: Pubkey = decode_32_const("BPFLoaderUpgradeab1e11111111111111111111111");
+
+    #[test]
+    fn test_create_program_address() {
+        let exceeded_seed = &[127; MAX_SEED_LEN + 1];
+        let max_seed = &[0; MAX_SEED_LEN];
+        let exceeded_seeds: &[&[u8]] = &[
+            &[1],
+            &[2],
+            &[3],
+            &[4],
+            &[5],
+            &[6],
+            &[7],
+            &[8],
+            &[9],
+            &[10],
+            &[11],
+            &[12],
+            &[13],
+            &[14],
+            &[15],
+            &[16],
+            &[17],
+        ];
+        let public_key = decode_32_const("SeedPubey1111111111111111111111111111111111");
+
+        assert_eq!(
+            create_program_address(&[exceeded_seed], &PROGRAM_ID),
+            Err(ProgramError::MaxSeedLengthExceeded)
+        );
+        assert_eq!(
+            create_program_address(&[b"short_seed", exceeded_seed], &PROGRAM_ID),
+            Err(ProgramError::MaxSeedLengthExceeded)
+        );
+        assert!(create_program_address(&[max_seed], &PROGRAM_ID).is_ok());
+        assert_eq!(
+            create_program_address(exceeded_seeds, &PROGRAM_ID),
+            Err(ProgramError::MaxSeedLengthExceeded)
+        );
+        assert!(create_program_address(&exceeded_seeds[..MAX_SEEDS], &PROGRAM_ID).is_ok());
+
+        // Addresses derived by the runtime.
+        assert_eq!(
+            create_program_address(&[b"", &[1]], &PROGRAM_ID),
+            Ok(decode_32_const(
+                "BwqrghZA2htAcqq8dzP1WDAhTXYTYWj7CHxF5j7TDBAe"
+            ))
+        );
+        assert_eq!(
+            create_program_address(&["\u{2609}".as_ref(), &[0]], &PROGRAM_ID),
+            Ok(decode_32_const(
+                "13yWmRpaTR4r5nAktwLqMpRNr28tnVUZw26rTvPSSB19"
+            ))
+        );
+        assert_eq!(
+            create_program_address(&[b"Talking", b"Squirrels"], &PROGRAM_ID),
+            Ok(decode_32_const(
+                "2fnQrngrQT4SeLcdToJAD96phoEjNL2man2kfRLCASVk"
+            ))
+        );
+        assert_eq!(
+            create_program_address(&[public_key.as_ref(), &[1]], &PROGRAM_ID),
+            Ok(decode_32_const(
+                "976ymqVnfE32QFe6NfGDctSvVa36LWnvYxhU6G2232YL"
+            ))
+        );
+    }
+
+    #[test]
+    fn test_find_program_address() {
+        let (address, bump) = find_program_address(&[b"Lil'", b"Bits"], &PROGRAM_ID);
+
+        assert_eq!(
+            address,
+            decode_32_const("H4feCuM8B43jxwbHAsUHDasw1raRkvWF6py4Fx7suB8N")
+        );
+        assert_eq!(bump, 254);
+        assert_eq!(
+            create_program_address(&[b"Lil'", b"Bits", &[bump]], &PROGRAM_ID),
+            Ok(address)
+        );
+        assert!(!is_on_curve(&address));
+
+        // there is no space for the bump seed
+        assert!(try_find_program_address(&[&[0u8] as &[u8]; MAX_SEEDS], &PROGRAM_ID).is_none());
+    }
+}
@@ -0,0 +1,178 @@
+//! Public key recovery from [secp256k1] ECDSA signatures.
+//!
+//! [secp256k1]: https://en.bitcoin.it/wiki/Secp256k1
+//!
+//! On non-`solana` targets, the public key is recovered in-process when the
+//! `secp256k1` feature is enabled.
+
+use crate::hash::keccak256;
+
+/// Length of a secp256k1 signature (`r` and `s` values).
+pub const SECP256K1_SIGNATURE_LENGTH: usize = 64;
+
+/// Length of an uncompressed secp256k1 public key, without the `0x04` prefix.
+pub const SECP256K1_PUBLIC_KEY_LENGTH: usize = 64;
+
+/// Length of an Ethereum address.
+pub const ETH_ADDRESS_LENGTH: usize = 20;
+
+/// Errors returned by [`recover`].
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+pub enum Secp256k1RecoverError {
+    /// The hash provided is invalid.
+    InvalidHash,
+
+    /// The recovery id provided is invalid.
+    InvalidRecoveryId,
+
+    /// The signature provided is invalid.
+    InvalidSignature,
+}
+
+impl From<u64> for Secp256k1RecoverError {
+    fn from(error: u64) -> Self {
+        match error {
+            1 => Self::InvalidHash,
+            2 => Self::InvalidRecoveryId,
+            // The syscall only returns `3` for invalid signatures; any other
+            // value is also treated as an invalid signature.
+            _ => Self::InvalidSignature,
+        }
+    }
+}
+
+impl From<Secp256k1RecoverError> for u64 {
+    fn from(error: Secp256k1RecoverError) -> Self {
+        match error {
+            Secp256k1RecoverError::InvalidHash => 1,
+            Secp256k1RecoverError::InvalidRecoveryId => 2,
+            Secp256k1RecoverError::InvalidSignature => 3,
+        }
+    }
+}
+
+/// Recover the public key that produced `signature` over the 32-byte message `hash`.
+///
+/// The `recovery_id` is a value in the range `0..=3`; Ethereum signatures
+/// encode it in the `v` value, which is either `27`/`28` or, for signatures
+/// following [EIP-155], `chain_id * 2 + 35`/`36`. Callers are responsible for
+/// converting `v` into a recovery id.
+///
+/// [EIP-155]: https://eips.ethereum.org/EIPS/eip-155
+///
+/// The returned public key is the 64-byte uncompressed key, without the `0x04`
+/// prefix.
+///
+/// **Warning**: The runtime does not reject signatures with a high `s` value.
+/// For every valid signature there is a second valid signature over the same
+/// message with a different `s` value, which recovers the same public key.
+/// Programs that rely on signatures being unique must check that `s` is in
+/// the lower half of the curve order.
+///
+/// # Panics
+///
+/// On non-`solana` targets, panics if the `secp256k1` feature is not enabled.
+#[inline]
+pub fn recover(
+    hash: &[u8; 32],
+    recovery_id: u8,
+    signature: &[u8; SECP256K1_SIGNATURE_LENGTH],
+) -> Result<[u8; SECP256K1_PUBLIC_KEY_LENGTH], Secp256k1RecoverError> {
+    #[cfg(target_os = "solana")]
+    {
+        let mut pubkey = core::mem::MaybeUninit::<[u8; SECP256K1_PUBLIC_KEY_LENGTH]>::uninit();
+
+        let result = unsafe {
+            crate::syscalls::sol_secp256k1_recover(
+                hash.as_ptr(),
+                recovery_id as u64,
+                signature.as_ptr(),
+                pubkey.as_mut_ptr() as *mut u8,
+            )
+        };
+
+        match result {
+            // SAFETY: The syscall has initialized the public key.
+            crate::SUCCESS => Ok(unsafe { pubkey.assume_init() }),
+            _ => Err(result.into()),
+        }
+    }
+
+    #[cfg(all(not(target_os = "solana"), feature = "secp256k1"))]
+    {
+        let message = libsecp256k1::Message::parse(hash);
+        let recovery_id = libsecp256k1::RecoveryId::parse(recovery_id)
+            .map_err(|_| Secp256k1RecoverError::InvalidRecoveryId)?;
+        let signature = libsecp256k1::Signature::parse_standard(signature)
+            .map_err(|_| Secp256k1RecoverError::InvalidSignature)?;
+        let public_key = libsecp256k1::recover(&message, &signature, &recovery_id)
+            .map_err(|_| Secp256k1RecoverError::InvalidSignature)?;
+
+        let mut pubkey = [0u8; SECP256K1_PUBLIC_KEY_LENGTH];
+        pubkey.copy_from_slice(&public_key.serialize()[1..]);
+        Ok(pubkey)
+    }
+
+    #[cfg(all(not(target_os = "solana"), not(feature = "secp256k1")))]
+    {
+        core::hint::black_box((hash, recovery_id, signature));
+        panic!("secp256k1 recover requires the `secp256k1` feature on non-solana targets")
+    }
+}
+
+/// Derive the Ethereum address of an uncompressed secp256k1 public key.
+///
+/// The address is the last 20 bytes of the Keccak-256 hash of the public key,
+/// as returned by [`recover`].
+///
+/// # Panics
+///
+/// On non-`solana` targets, panics if the `hash` feature is not enabled.
+#[inline]
+pub fn eth_address(pubkey: &[u8; SECP256K1_PUBLIC_KEY_LENGTH]) -> [u8; ETH_ADDRESS_LENGTH] {
+    let hash = keccak256(&[pubkey]);
+    let mut address = [0u8; ETH_ADDRESS_LENGTH];
+    address.copy_from_slice(&hash[32 - ETH_ADDRESS_LENGTH..]);
+    address
+}
+
+#[cfg(all(test, feature = "secp256k1"))]
+mod tests {
+    use super::*;
+
+    /// Address of the secret key `[0x46; 32]`, from the EIP-155 example.
+    const ADDRESS: [u8; ETH_ADDRESS_LENGTH] = [
+        0x9d, 0x8a, 0x62, 0xf6, 0x56, 0xa8, 0xd1, 0x61, 0x5c, 0x12, 0x94, 0xfd, 0x71, 0xe9, 0xcf,
+        0xb3, 0xe4, 0x85, 0x5a, 0x4f,
+    ];
+
+    /// Signature of `keccak256("hello world")` by the secret key `[0x46; 32]`.
+    const SIGNATURE: [u8; SECP256K1_SIGNATURE_LENGTH] = [
+        0x92, 0xe0, 0xec, 0x96, 0xa9, 0x17, 0x19, 0xd0, 0x92, 0x8b, 0x22, 0x08, 0x76, 0x85, 0x54,
+        0x26, 0xed, 0xd8, 0x04, 0x11, 0xc4, 0xf4, 0xd1, 0x78, 0x3a, 0xda, 0x15, 0xc4, 0xe9, 0x42,
+        0xf0, 0xbf, 0x77, 0x4a, 0xbd, 0xd2, 0xb6, 0xee, 0x99, 0x5a, 0xa6, 0x5b, 0x21, 0x99, 0x8f,
+        0x0a, 0xac, 0xd9, 0x0c, 0x2d, 0xc9, 0x8d, 0x8d, 0x84, 0xe6, 0x76, 0xa6, 0x83, 0xb5, 0x03,
+        0x77, 0x6e, 0x54, 0xdf,
+    ];
+
+    #[test]
+    fn test_recover() {
+        let hash = keccak256(&[b"hello world"]);
+
+        let pubkey = recover(&hash, 0, &SIGNATURE).unwrap();
+        assert_eq!(eth_address(&pubkey), ADDRESS);
+
+        // The other recovery id yields a different key.
+        let pubkey = recover(&hash, 1, &SIGNATURE).unwrap();
+        assert_ne!(eth_address(&pubkey), ADDRESS);
+
+        assert_eq!(
+            recover(&hash, 4, &SIGNATURE),
+            Err(Secp256k1RecoverError::InvalidRecoveryId)
+        );
+        assert_eq!(
+            recover(&hash, 0, &[0xff; SECP256K1_SIGNATURE_LENGTH]),
+            Err(Secp256k1RecoverError::InvalidSignature)
+        );
+    }
+}
@@ -0,0 +1,202 @@
+//! Epoch rewards for the current epoch.
+//!
+//! The epoch rewards sysvar is active while the rewards of the previous epoch
+//! are being distributed across partitions of accounts, which happens at the
+//! beginning of each epoch.
+
+use super::Sysvar;
+use crate::{
+    account_info::{AccountInfo, Ref},
+    program_error::ProgramError,
+    pubkey::Pubkey,
+};
+
+/// The ID of the epoch rewards sysvar.
+pub const EPOCH_REWARDS_ID: Pubkey = [
+    6, 167, 213, 23, 24, 220, 63, 238, 2, 165, 88, 191, 131, 206, 102, 225, 68, 66, 42, 28, 52,
+    149, 11, 39, 193, 134, 155, 90, 156, 0, 0, 0,
+];
+
+/// Epoch rewards sysvar data.
+///
+/// The `total_points` and `active` fields are stored as bytes so that the
+/// struct has the same layout as the sysvar account data while only requiring
+/// an 8-byte alignment.
+#[repr(C)]
+#[derive(Clone, Debug, Default, Eq, PartialEq)]
+pub struct EpochRewards {
+    /// The starting block height of the rewards distribution in the current
+    /// epoch.
+    pub distribution_starting_block_height: u64,
+
+    /// Number of partitions in the rewards distribution in the current epoch,
+    /// used to generate an `EpochRewardsHasher`.
+    pub num_partitions: u64,
+
+    /// The blockhash of the parent block of the first block in the epoch, used
+    /// to seed an `EpochRewardsHasher`.
+    pub parent_blockhash: [u8; 32],
+
+    /// The total rewards points calculated for the current epoch, where points
+    /// equals the sum of (delegated stake * credits observed) for all
+    /// delegations.
+    total_points: [u8; 16],
+
+    /// The total rewards calculated for the current epoch. This may be greater
+    /// than the total `distributed_rewards` at the end of the rewards period,
+    /// due to rounding and inability to deliver rewards smaller than 1 lamport.
+    pub total_rewards: u64,
+
+    /// The rewards currently distributed for the current epoch, in lamports.
+    pub distributed_rewards: u64,
+
+    /// Whether the rewards period (including calculation and distribution) is
+    /// active.
+    active: u8,
+}
+
+impl EpochRewards {
+    /// The length of the `EpochRewards` sysvar account data.
+    pub const LEN: usize = 8 + 8 + 32 + 16 + 8 + 8 + 1;
+
+    /// Return an `EpochRewards` from the given account info.
+    ///
+    /// This method performs a check on the account info key.
+    #[inline]
+    pub fn from_account_info(
+        account_info: &AccountInfo,
+    ) -> Result<Ref<'_, EpochRewards>, ProgramError> {
+        if account_info.key() != &EPOCH_REWARDS_ID {
+            return Err(ProgramError::InvalidArgument);
+        }
+        Ok(Ref::map(account_info.try_borrow_data()?, |data| unsafe {
+            Self::from_bytes_unchecked(data)
+        }))
+    }
+
+    /// Return an `EpochRewards` from the given account info.
+    ///
+    /// This method performs a check on the account info key, but does not
+    /// perform the borrow check.
+    ///
+    /// # Safety
+    ///
+    /// The caller must ensure that it is safe to borrow the account data – e.g., there are
+    /// no mutable borrows of the account data.
+    #[inline]
+    pub unsafe fn from_account_info_unchecked(
+        account_info: &AccountInfo,
+    ) -> Result<&Self, ProgramError> {
+        if account_info.key() != &EPOCH_REWARDS_ID {
+            return Err(ProgramError::InvalidArgument);
+        }
+        Ok(Self::from_bytes_unchecked(
+            account_info.borrow_data_unchecked(),
+        ))
+    }
+
+    /// Return an `EpochRewards` from the given bytes.
+    ///
+    /// This method performs a length validation. The caller must ensure that `bytes` contains
+    /// a valid representation of `EpochRewards`.
+    #[inline]
+    pub fn from_bytes(bytes: &[u8]) -> Result<&Self, ProgramError> {
+        if bytes.len() != Self::LEN {
+            return Err(ProgramError::InvalidArgument);
+        }
+        Ok(unsafe { Self::from_bytes_unchecked(bytes) })
+    }
+
+    /// Return an `EpochRewards` from the given bytes.
+    ///
+    /// # Safety
+    ///
+    /// The caller must ensure that `bytes` contains a valid representation of `EpochRewards`
+    /// and that is has the expected length.
+    #[inline]
+    pub unsafe fn from_bytes_unchecked(bytes: &[u8]) -> &Self {
+        &*(bytes.as_ptr() as *const EpochRewards)
+    }
+
+    /// The total rewards points calculated for the current epoch.
+    #[inline]
+    pub fn total_points(&self) -> u128 {
+        u128::from_le_bytes(self.total_points)
+    }
+
+    /// Whether the rewards period (including calculation and distribution) is
+    /// active.
+    #[inline]
+    pub fn active(&self) -> bool {
+        self.active != 0
+    }
+
+    /// The rewards of the current epoch that are still to be distributed, in
+    /// lamports.
+    #[inline]
+    pub fn remaining_rewards(&self) -> u64 {
+        self.total_rewards.saturating_sub(self.distributed_rewards)
+    }
+}
+
+impl Sysvar for EpochRewards {
+    fn get() -> Result<Self, ProgramError> {
+        #[cfg(target_os = "solana")]
+        {
+            // The runtime requires the address to be aligned as its own
+            // representation of the sysvar, which contains a `u128`.
+            #[repr(C, align(16))]
+            #[derive(Default)]
+            struct Aligned(EpochRewards, [u8; 8]);
+
+            let mut var = Aligned::default();
+            let var_addr = &mut var as *mut _ as *mut u8;
+
+            let result = unsafe { crate::syscalls::sol_get_epoch_rewards_sysvar(var_addr) };
+
+            match result {
+                crate::SUCCESS => Ok(var.0),
+                e => Err(e.into()),
+            }
+        }
+
+        #[cfg(not(target_os = "solana"))]
+        super::get_host_sysvar::<Self>()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_from_bytes() {
+        // Account data is 8-byte aligned.
+        #[repr(align(8))]
+        struct Data([u8; EpochRewards::LEN + 7]);
+
+        let mut bytes = Data([0u8; EpochRewards::LEN + 7]);
+        let data = &mut bytes.0[..EpochRewards::LEN];
+        data[0..8].copy_from_slice(&42u64.to_le_bytes());
+        data[8..16].copy_from_slice(&4u64.to_le_bytes());
+        data[16..48].copy_from_slice(&[7; 32]);
+        data[48..64].copy_from_slice(&(u64::MAX as u128 + 1).to_le_bytes());
+        data[64..72].copy_from_slice(&1_000u64.to_le_bytes());
+        data[72..80].copy_from_slice(&400u64.to_le_bytes());
+        data[80] = 1;
+
+        let epoch_rewards = EpochRewards::from_bytes(data).unwrap();
+
+        assert_eq!(epoch_rewards.distribution_starting_block_height, 42);
+        assert_eq!(epoch_rewards.num_partitions, 4);
+        assert_eq!(epoch_rewards.parent_blockhash, [7; 32]);
+        assert_eq!(epoch_rewards.total_points(), u64::MAX as u128 + 1);
+        assert!(epoch_rewards.active());
+        assert_eq!(epoch_rewards.remaining_rewards(), 600);
+
+        assert_eq!(
+            EpochRewards::from_bytes(&bytes.0),
+            Err(ProgramError::InvalidArgument)
+        );
+    }
+}
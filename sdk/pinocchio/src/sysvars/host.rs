@@ -0,0 +1,183 @@
+//! Host-side sysvar values.
+//!
+//! On non-`solana` targets there is no runtime to provide sysvars. This module
+//! holds the values returned by [`Sysvar::get`] and [`get_sysvar_bytes`] on the
+//! current thread, so tests can configure them before calling program code:
+//!
+//! ```
+//! use pinocchio::sysvars::{clock::Clock, host, Sysvar};
+//!
+//! host::set_sysvar(Clock {
+//!     slot: 42,
+//!     ..Clock::default()
+//! });
+//!
+//! assert_eq!(Clock::get().unwrap().slot, 42);
+//! ```
+//!
+//! Typed values and account data are stored separately: [`set_sysvar`] only
+//! sets the value returned by [`Sysvar::get`], and [`set_sysvar_data`] only
+//! sets the data read by [`get_sysvar_bytes`]. A test that reads a sysvar both
+//! ways must set both.
+//!
+//! Values are stored per thread, so tests running in parallel do not observe
+//! each other's sysvars.
+//!
+//! [`get_sysvar_bytes`]: super::get_sysvar_bytes
+
+use std::{any::Any, any::TypeId, boxed::Box, cell::RefCell, collections::HashMap, vec::Vec};
+
+use super::{Sysvar, OFFSET_LENGTH_EXCEEDS_SYSVAR, SYSVAR_NOT_FOUND};
+use crate::pubkey::Pubkey;
+
+/// A sysvar value, together with a function to clone it.
+struct HostSysvar {
+    value: Box<dyn Any>,
+    clone: fn(&dyn Any) -> Box<dyn Any>,
+}
+
+std::thread_local! {
+    static SYSVARS: RefCell<HashMap<TypeId, HostSysvar>> = RefCell::new(HashMap::new());
+
+    static SYSVAR_DATA: RefCell<HashMap<Pubkey, Vec<u8>>> = RefCell::new(HashMap::new());
+}
+
+/// Set the value returned by [`Sysvar::get`] for `T` on the current thread.
+///
+/// This does not set the account data of the sysvar: partial reads through
+/// [`get_sysvar_bytes`](super::get_sysvar_bytes) still return the data set by
+/// [`set_sysvar_data`].
+pub fn set_sysvar<T: Sysvar + Clone + 'static>(value: T) {
+    fn clone<T: Clone + 'static>(value: &dyn Any) -> Box<dyn Any> {
+        // The value is always stored under the type id of `T`.
+        Box::new(value.downcast_ref::<T>().unwrap().clone())
+    }
+
+    SYSVARS.with(|sysvars| {
+        sysvars.borrow_mut().insert(
+            TypeId::of::<T>(),
+            HostSysvar {
+                value: Box::new(value),
+                clone: clone::<T>,
+            },
+        )
+    });
+}
+
+/// Set the account data of the sysvar `id` on the current thread.
+///
+/// The data is read by [`get_sysvar_bytes`](super::get_sysvar_bytes), e.g. to
+/// fetch entries of the slot hashes or stake history sysvars. It is not used by
+/// [`Sysvar::get`], which returns the value set by [`set_sysvar`].
+pub fn set_sysvar_data(id: Pubkey, data: &[u8]) {
+    SYSVAR_DATA.with(|sysvar_data| sysvar_data.borrow_mut().insert(id, data.to_vec()));
+}
+
+/// Remove all sysvar values and data set on the current thread.
+pub fn clear_sysvars() {
+    SYSVARS.with(|sysvars| sysvars.borrow_mut().clear());
+    SYSVAR_DATA.with(|sysvar_data| sysvar_data.borrow_mut().clear());
+}
+
+/// Return the value set for `T` on the current thread, if any.
+pub(crate) fn get_sysvar<T: 'static>() -> Option<T> {
+    SYSVARS.with(|sysvars| {
+        sysvars.borrow().get(&TypeId::of::<T>()).map(|sysvar| {
+            // The value is always stored under the type id of `T`.
+            *(sysvar.clone)(sysvar.value.as_ref())
+                .downcast::<T>()
+                .unwrap()
+        })
+    })
+}
+
+/// Copy the data set for the sysvar `id`, starting at `offset`, into `dst`.
+///
+/// Returns the same values as the `sol_get_sysvar` syscall.
+pub(crate) fn read_sysvar_data(id: &Pubkey, offset: u64, dst: &mut [u8]) -> u64 {
+    SYSVAR_DATA.with(|sysvar_data| match sysvar_data.borrow().get(id) {
+        Some(data) => {
+            let start = offset as usize;
+            match start
+                .checked_add(dst.len())
+                .and_then(|end| data.get(start..end))
+            {
+                Some(bytes) => {
+                    dst.copy_from_slice(bytes);
+                    crate::SUCCESS
+                }
+                None => OFFSET_LENGTH_EXCEEDS_SYSVAR,
+            }
+        }
+        None => SYSVAR_NOT_FOUND,
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{
+        program_error::ProgramError,
+        sysvars::{
+            clock::Clock,
+            get_sysvar_bytes, get_sysvar_u64,
+            rent::{Rent, RENT_ID},
+            slot_hashes::{self, SLOT_HASHES_ID},
+        },
+    };
+
+    #[test]
+    fn test_set_sysvar() {
+        assert_eq!(Rent::get().err(), Some(ProgramError::UnsupportedSysvar));
+
+        set_sysvar(Rent {
+            lamports_per_byte_year: 10,
+            ..Rent::default()
+        });
+        set_sysvar(Clock {
+            slot: 7,
+            ..Clock::default()
+        });
+
+        assert_eq!(Rent::get().unwrap().lamports_per_byte_year, 10);
+        assert_eq!(Clock::get().unwrap().slot, 7);
+
+        // The account data of the sysvar is set separately.
+        assert_eq!(
+            get_sysvar_u64(&RENT_ID, 0),
+            Err(ProgramError::UnsupportedSysvar)
+        );
+
+        clear_sysvars();
+
+        assert_eq!(Clock::get().err(), Some(ProgramError::UnsupportedSysvar));
+    }
+
+    #[test]
+    fn test_set_sysvar_data() {
+        let mut data = Vec::new();
+        data.extend_from_slice(&2u64.to_le_bytes());
+        data.extend_from_slice(&9u64.to_le_bytes());
+        data.extend_from_slice(&[9; 32]);
+        data.extend_from_slice(&4u64.to_le_bytes());
+        data.extend_from_slice(&[4; 32]);
+
+        assert_eq!(
+            get_sysvar_u64(&SLOT_HASHES_ID, 0),
+            Err(ProgramError::UnsupportedSysvar)
+        );
+
+        set_sysvar_data(SLOT_HASHES_ID, &data);
+
+        assert_eq!(slot_hashes::fetch_len(), Ok(2));
+        assert_eq!(slot_hashes::fetch_entry(1).unwrap().slot(), 4);
+        assert_eq!(slot_hashes::fetch_hash(9), Ok(Some([9; 32])));
+        assert_eq!(slot_hashes::fetch_hash(5), Ok(None));
+
+        let mut bytes = [0u8; 8];
+        assert_eq!(
+            get_sysvar_bytes(&SLOT_HASHES_ID, data.len() as u64 - 4, &mut bytes),
+            Err(ProgramError::InvalidArgument)
+        );
+    }
+}
@@ -0,0 +1,55 @@
+//! Information about the last restart slot (hard fork).
+//!
+//! The last restart slot sysvar holds the slot of the last cluster restart,
+//! or `0` if there has been none.
+
+use super::{clock::Slot, Sysvar};
+use crate::{impl_sysvar_get, pubkey::Pubkey};
+
+/// The ID of the last restart slot sysvar.
+pub const LAST_RESTART_SLOT_ID: Pubkey = [
+    6, 167, 213, 23, 25, 6, 221, 225, 205, 63, 148, 125, 202, 180, 200, 244, 244, 245, 27, 173, 15,
+    152, 19, 184, 0, 210, 137, 71, 31, 192, 0, 0,
+];
+
+/// Last restart slot sysvar data.
+#[repr(C)]
+#[derive(Clone, Debug, Default, Eq, PartialEq)]
+pub struct LastRestartSlot {
+    /// The last restart slot.
+    pub last_restart_slot: Slot,
+}
+
+impl Sysvar for LastRestartSlot {
+    impl_sysvar_get!(sol_get_last_restart_slot);
+}
+
+/// Set the value returned by [`LastRestartSlot::get`] on the current thread.
+///
+/// This is a shorthand for [`set_sysvar`](super::host::set_sysvar), only
+/// available on non-`solana` targets, where there is no runtime to provide the
+/// sysvar.
+#[cfg(all(not(target_os = "solana"), feature = "std"))]
+pub fn set_last_restart_slot(last_restart_slot: Slot) {
+    super::host::set_sysvar(LastRestartSlot { last_restart_slot });
+}
+
+#[cfg(all(test, feature = "std"))]
+mod tests {
+    use super::*;
+    use crate::program_error::ProgramError;
+
+    #[test]
+    fn test_get() {
+        assert_eq!(LastRestartSlot::get(), Err(ProgramError::UnsupportedSysvar));
+
+        set_last_restart_slot(42);
+
+        assert_eq!(
+            LastRestartSlot::get(),
+            Ok(LastRestartSlot {
+                last_restart_slot: 42
+            })
+        );
+    }
+}
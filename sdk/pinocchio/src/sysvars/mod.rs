@@ -1,11 +1,18 @@
 //! Provides access to cluster system accounts.
 
-use crate::program_error::ProgramError;
+use crate::{program_error::ProgramError, pubkey::Pubkey};
 
 pub mod clock;
+pub mod epoch_rewards;
+pub mod epoch_schedule;
 pub mod fees;
+#[cfg(all(not(target_os = "solana"), feature = "std"))]
+pub mod host;
 pub mod instructions;
+pub mod last_restart_slot;
 pub mod rent;
+pub mod slot_hashes;
+pub mod stake_history;
 
 /// A type that holds sysvar data.
 pub trait Sysvar: Default + Sized {
@@ -17,6 +24,10 @@ pub trait Sysvar: Default + Sized {
     ///
     /// Not all sysvars support this method. If not, it returns
     /// [`ProgramError::UnsupportedSysvar`].
+    ///
+    /// On non-`solana` targets, it returns the value set through
+    /// `host::set_sysvar` when the `std` feature is enabled, and
+    /// [`ProgramError::UnsupportedSysvar`] otherwise.
     fn get() -> Result<Self, ProgramError> {
         Err(ProgramError::UnsupportedSysvar)
     }
@@ -27,19 +38,166 @@ pub trait Sysvar: Default + Sized {
 macro_rules! impl_sysvar_get {
     ($syscall_name:ident) => {
         fn get() -> Result<Self, $crate::program_error::ProgramError> {
-            let mut var = Self::default();
-            let var_addr = &mut var as *mut _ as *mut u8;
-
             #[cfg(target_os = "solana")]
-            let result = unsafe { $crate::syscalls::$syscall_name(var_addr) };
+            {
+                let mut var = Self::default();
+                let var_addr = &mut var as *mut _ as *mut u8;
 
-            #[cfg(not(target_os = "solana"))]
-            let result = core::hint::black_box(var_addr as *const _ as u64);
+                let result = unsafe { $crate::syscalls::$syscall_name(var_addr) };
 
-            match result {
-                $crate::SUCCESS => Ok(var),
-                e => Err(e.into()),
+                match result {
+                    $crate::SUCCESS => Ok(var),
+                    e => Err(e.into()),
+                }
             }
+
+            #[cfg(not(target_os = "solana"))]
+            $crate::sysvars::get_host_sysvar::<Self>()
         }
     };
 }
+
+/// Return the value of the sysvar `T` on non-`solana` targets.
+///
+/// This is used by [`impl_sysvar_get!`](crate::impl_sysvar_get), which is
+/// expanded in crates that might not enable the `std` feature themselves.
+#[doc(hidden)]
+#[cfg(not(target_os = "solana"))]
+pub fn get_host_sysvar<T: 'static>() -> Result<T, ProgramError> {
+    #[cfg(feature = "std")]
+    {
+        host::get_sysvar::<T>().ok_or(ProgramError::UnsupportedSysvar)
+    }
+
+    #[cfg(not(feature = "std"))]
+    {
+        Err(ProgramError::UnsupportedSysvar)
+    }
+}
+
+/// Return value of `sol_get_sysvar` when the requested range is out of the
+/// sysvar data bounds.
+const OFFSET_LENGTH_EXCEEDS_SYSVAR: u64 = 1;
+
+/// Return value of `sol_get_sysvar` when the sysvar is not found.
+const SYSVAR_NOT_FOUND: u64 = 2;
+
+/// Copy `dst.len()` bytes of the sysvar data, starting at `offset`, into `dst`.
+///
+/// This allows reading part of a sysvar – e.g., a single entry of a large
+/// sysvar – without the sysvar account being passed to the program.
+///
+/// # Errors
+///
+/// Returns [`ProgramError::InvalidArgument`] if the requested range exceeds
+/// the sysvar data and [`ProgramError::UnsupportedSysvar`] if `id` is not a
+/// sysvar that can be read.
+#[inline]
+pub fn get_sysvar_bytes(id: &Pubkey, offset: u64, dst: &mut [u8]) -> Result<(), ProgramError> {
+    #[cfg(target_os = "solana")]
+    let result = unsafe {
+        crate::syscalls::sol_get_sysvar(
+            id as *const _ as *const u8,
+            dst.as_mut_ptr(),
+            offset,
+            dst.len() as u64,
+        )
+    };
+
+    #[cfg(all(not(target_os = "solana"), feature = "std"))]
+    let result = host::read_sysvar_data(id, offset, dst);
+
+    #[cfg(all(not(target_os = "solana"), not(feature = "std")))]
+    let result = {
+        core::hint::black_box((id, offset, dst));
+        SYSVAR_NOT_FOUND
+    };
+
+    match result {
+        crate::SUCCESS => Ok(()),
+        OFFSET_LENGTH_EXCEEDS_SYSVAR => Err(ProgramError::InvalidArgument),
+        // `SYSVAR_NOT_FOUND` and unexpected errors.
+        _ => Err(ProgramError::UnsupportedSysvar),
+    }
+}
+
+/// Read a value of type `T` from the sysvar data, starting at `offset`.
+///
+/// # Errors
+///
+/// Returns the same errors as [`get_sysvar_bytes`].
+///
+/// # Safety
+///
+/// The caller must ensure that `T` is a `#[repr(C)]` type for which any bit pattern
+/// is valid, and that its layout matches the sysvar data at `offset`.
+#[inline]
+pub unsafe fn get_sysvar_as<T>(id: &Pubkey, offset: u64) -> Result<T, ProgramError> {
+    let mut var = core::mem::MaybeUninit::<T>::uninit();
+    let bytes =
+        core::slice::from_raw_parts_mut(var.as_mut_ptr() as *mut u8, core::mem::size_of::<T>());
+
+    get_sysvar_bytes(id, offset, bytes)?;
+    // SAFETY: All bytes of `var` have been written by `get_sysvar_bytes`.
+    Ok(var.assume_init())
+}
+
+/// Read a little-endian `u64` from the sysvar data, starting at `offset`.
+///
+/// # Errors
+///
+/// Returns the same errors as [`get_sysvar_bytes`].
+#[inline]
+pub fn get_sysvar_u64(id: &Pubkey, offset: u64) -> Result<u64, ProgramError> {
+    let mut bytes = [0u8; 8];
+    get_sysvar_bytes(id, offset, &mut bytes)?;
+    Ok(u64::from_le_bytes(bytes))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_get_sysvar_not_found() {
+        let mut bytes = [0u8; 8];
+        assert_eq!(
+            get_sysvar_bytes(&[1; 32], 0, &mut bytes),
+            Err(ProgramError::UnsupportedSysvar)
+        );
+        assert_eq!(
+            get_sysvar_u64(&[1; 32], 0),
+            Err(ProgramError::UnsupportedSysvar)
+        );
+    }
+
+    #[cfg(feature = "std")]
+    #[test]
+    fn test_get_sysvar() {
+        const ID: Pubkey = [2; 32];
+
+        let mut data = [0u8; 24];
+        data[..8].copy_from_slice(&1u64.to_le_bytes());
+        data[8..16].copy_from_slice(&2u64.to_le_bytes());
+        data[16..].copy_from_slice(&3u64.to_le_bytes());
+        host::set_sysvar_data(ID, &data);
+
+        let mut bytes = [0u8; 4];
+        assert_eq!(get_sysvar_bytes(&ID, 8, &mut bytes), Ok(()));
+        assert_eq!(bytes, [2, 0, 0, 0]);
+
+        assert_eq!(get_sysvar_u64(&ID, 16), Ok(3));
+        assert_eq!(unsafe { get_sysvar_as::<[u64; 2]>(&ID, 0) }, Ok([1, 2]));
+
+        // The range must be within the sysvar data.
+        assert_eq!(get_sysvar_u64(&ID, 17), Err(ProgramError::InvalidArgument));
+        assert_eq!(
+            get_sysvar_u64(&ID, u64::MAX),
+            Err(ProgramError::InvalidArgument)
+        );
+        assert_eq!(
+            unsafe { get_sysvar_as::<[u64; 4]>(&ID, 0) },
+            Err(ProgramError::InvalidArgument)
+        );
+    }
+}
@@ -0,0 +1,291 @@
+//! Hashes of the most recent slots.
+//!
+//! The slot hashes sysvar holds up to [`MAX_ENTRIES`] `(slot, hash)` entries,
+//! ordered from the most recent slot to the oldest one.
+//!
+//! The account data is too large to be loaded by [`super::Sysvar::get`].
+//! Programs that receive the sysvar account can read it through a
+//! [`SlotHashes`] view, while the `fetch_*` functions read individual entries
+//! through the `sol_get_sysvar` syscall.
+
+use super::{clock::Slot, get_sysvar_as, get_sysvar_u64};
+use crate::{
+    account_info::{AccountInfo, Ref},
+    hash::Hash,
+    program_error::ProgramError,
+    pubkey::Pubkey,
+};
+
+use core::{mem::size_of, ops::Deref};
+
+/// The ID of the slot hashes sysvar.
+pub const SLOT_HASHES_ID: Pubkey = [
+    6, 167, 213, 23, 25, 47, 10, 175, 198, 242, 101, 227, 251, 119, 204, 122, 218, 130, 197, 41,
+    208, 190, 59, 19, 110, 45, 0, 85, 32, 0, 0, 0,
+];
+
+/// The maximum number of entries in the slot hashes sysvar.
+pub const MAX_ENTRIES: usize = 512;
+
+/// The size of the entry count prefix of the sysvar data.
+const NUM_ENTRIES_LEN: usize = size_of::<u64>();
+
+/// An entry of the slot hashes sysvar.
+#[repr(C)]
+#[derive(Clone, Debug, Default, Eq, PartialEq)]
+pub struct SlotHashEntry {
+    /// The slot, as little-endian bytes.
+    slot: [u8; 8],
+
+    /// The bank hash of the slot.
+    pub hash: Hash,
+}
+
+impl SlotHashEntry {
+    /// The length of an entry in the sysvar data.
+    pub const LEN: usize = size_of::<Self>();
+
+    /// The slot of the entry.
+    #[inline(always)]
+    pub fn slot(&self) -> Slot {
+        Slot::from_le_bytes(self.slot)
+    }
+}
+
+/// A view over the slot hashes sysvar account data.
+pub struct SlotHashes<T>
+where
+    T: Deref<Target = [u8]>,
+{
+    data: T,
+}
+
+impl<T> SlotHashes<T>
+where
+    T: Deref<Target = [u8]>,
+{
+    /// Creates a new `SlotHashes` struct.
+    ///
+    /// `data` is the slot hashes sysvar account data.
+    ///
+    /// # Errors
+    ///
+    /// Returns [`ProgramError::AccountDataTooSmall`] if `data` is shorter than the
+    /// entries it declares, and [`ProgramError::InvalidAccountData`] if it
+    /// declares more than [`MAX_ENTRIES`] entries.
+    #[inline]
+    pub fn new(data: T) -> Result<Self, ProgramError> {
+        if data.len() < NUM_ENTRIES_LEN {
+            return Err(ProgramError::AccountDataTooSmall);
+        }
+
+        // SAFETY: The data is at least `NUM_ENTRIES_LEN` bytes long.
+        let slot_hashes = unsafe { Self::new_unchecked(data) };
+        let len = slot_hashes.len();
+
+        if len > MAX_ENTRIES {
+            return Err(ProgramError::InvalidAccountData);
+        }
+
+        // Compared by division, since `len` is read from the data.
+        if len > (slot_hashes.data.len() - NUM_ENTRIES_LEN) / SlotHashEntry::LEN {
+            return Err(ProgramError::AccountDataTooSmall);
+        }
+
+        Ok(slot_hashes)
+    }
+
+    /// Creates a new `SlotHashes` struct.
+    ///
+    /// `data` is the slot hashes sysvar account data.
+    ///
+    /// # Safety
+    ///
+    /// This function is unsafe because it does not check if the provided data is
+    /// a valid representation of the slot hashes sysvar data.
+    #[inline(always)]
+    pub unsafe fn new_unchecked(data: T) -> Self {
+        SlotHashes { data }
+    }
+
+    /// The number of entries.
+    #[inline(always)]
+    pub fn len(&self) -> usize {
+        // SAFETY: The first 8 bytes of the data represent the number of entries.
+        unsafe { u64::from_le_bytes(*(self.data.as_ptr() as *const [u8; 8])) as usize }
+    }
+
+    /// Indicate whether there are no entries.
+    #[inline(always)]
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+
+    /// All the entries, from the most recent slot to the oldest one.
+    #[inline(always)]
+    pub fn entries(&self) -> &[SlotHashEntry] {
+        // SAFETY: The entries follow the number of entries and `SlotHashEntry` has
+        // an alignment of 1.
+        unsafe {
+            core::slice::from_raw_parts(
+                self.data.as_ptr().add(NUM_ENTRIES_LEN) as *const SlotHashEntry,
+                self.len(),
+            )
+        }
+    }
+
+    /// Get the entry at the specified index.
+    #[inline(always)]
+    pub fn get(&self, index: usize) -> Option<&SlotHashEntry> {
+        self.entries().get(index)
+    }
+
+    /// Get the index of the entry of the specified slot.
+    #[inline]
+    pub fn position(&self, slot: Slot) -> Option<usize> {
+        self.entries()
+            .binary_search_by(|entry| slot.cmp(&entry.slot()))
+            .ok()
+    }
+
+    /// Get the hash of the specified slot.
+    #[inline]
+    pub fn get_hash(&self, slot: Slot) -> Option<&Hash> {
+        self.position(slot).map(|index| &self.entries()[index].hash)
+    }
+}
+
+impl<'a> TryFrom<&'a AccountInfo> for SlotHashes<Ref<'a, [u8]>> {
+    type Error = ProgramError;
+
+    #[inline(always)]
+    fn try_from(account_info: &'a AccountInfo) -> Result<Self, Self::Error> {
+        if account_info.key() != &SLOT_HASHES_ID {
+            return Err(ProgramError::UnsupportedSysvar);
+        }
+
+        SlotHashes::new(account_info.try_borrow_data()?)
+    }
+}
+
+/// Fetch the number of entries of the slot hashes sysvar.
+#[inline]
+pub fn fetch_len() -> Result<usize, ProgramError> {
+    get_sysvar_u64(&SLOT_HASHES_ID, 0).map(|len| len as usize)
+}
+
+/// Fetch the entry at the specified index of the slot hashes sysvar.
+///
+/// # Errors
+///
+/// Returns [`ProgramError::InvalidArgument`] if the index is out of bounds.
+#[inline]
+pub fn fetch_entry(index: usize) -> Result<SlotHashEntry, ProgramError> {
+    if index >= fetch_len()? {
+        return Err(ProgramError::InvalidArgument);
+    }
+
+    // SAFETY: `SlotHashEntry` is `#[repr(C)]` and any bit pattern is valid.
+    unsafe {
+        get_sysvar_as(
+            &SLOT_HASHES_ID,
+            (NUM_ENTRIES_LEN + index * SlotHashEntry::LEN) as u64,
+        )
+    }
+}
+
+/// Fetch the hash of the specified slot from the slot hashes sysvar.
+///
+/// The entries are binary searched, fetching one entry per step.
+#[inline]
+pub fn fetch_hash(slot: Slot) -> Result<Option<Hash>, ProgramError> {
+    let (mut low, mut high) = (0, fetch_len()?);
+
+    while low < high {
+        let middle = low + (high - low) / 2;
+        // SAFETY: `SlotHashEntry` is `#[repr(C)]` and any bit pattern is valid.
+        let entry: SlotHashEntry = unsafe {
+            get_sysvar_as(
+                &SLOT_HASHES_ID,
+                (NUM_ENTRIES_LEN + middle * SlotHashEntry::LEN) as u64,
+            )?
+        };
+
+        match slot.cmp(&entry.slot()) {
+            core::cmp::Ordering::Equal => return Ok(Some(entry.hash)),
+            // Entries are ordered from the most recent slot.
+            core::cmp::Ordering::Greater => high = middle,
+            core::cmp::Ordering::Less => low = middle + 1,
+        }
+    }
+
+    Ok(None)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    extern crate std;
+    use std::vec::Vec;
+
+    fn slot_hashes_data(slots: &[Slot]) -> Vec<u8> {
+        let mut data = Vec::new();
+        data.extend_from_slice(&(slots.len() as u64).to_le_bytes());
+        for slot in slots {
+            data.extend_from_slice(&slot.to_le_bytes());
+            data.extend_from_slice(&[*slot as u8; 32]);
+        }
+        data
+    }
+
+    #[test]
+    fn test_slot_hashes() {
+        let data = slot_hashes_data(&[20, 15, 12, 7, 3]);
+        let slot_hashes = SlotHashes::new(data.as_slice()).unwrap();
+
+        assert_eq!(slot_hashes.len(), 5);
+        assert_eq!(slot_hashes.get(1).map(SlotHashEntry::slot), Some(15));
+        assert!(slot_hashes.get(5).is_none());
+
+        for (index, slot) in [20, 15, 12, 7, 3].into_iter().enumerate() {
+            assert_eq!(slot_hashes.position(slot), Some(index));
+            assert_eq!(slot_hashes.get_hash(slot), Some(&[slot as u8; 32]));
+        }
+
+        for slot in [0, 4, 13, 21] {
+            assert_eq!(slot_hashes.get_hash(slot), None);
+        }
+    }
+
+    #[test]
+    fn test_slot_hashes_too_small() {
+        let data = slot_hashes_data(&[2, 1]);
+
+        assert!(SlotHashes::new(&data[..4]).is_err());
+        assert!(SlotHashes::new(&data[..data.len() - 1]).is_err());
+
+        let slot_hashes = SlotHashes::new(&data[..8 + SlotHashEntry::LEN * 2]).unwrap();
+        assert!(!slot_hashes.is_empty());
+    }
+
+    #[test]
+    fn test_slot_hashes_invalid_len() {
+        // `40 * 2^61` wraps to `0`.
+        let mut data = [0u8; 16];
+        data[..8].copy_from_slice(&(1u64 << 61).to_le_bytes());
+        assert_eq!(
+            SlotHashes::new(data.as_slice()).err(),
+            Some(ProgramError::InvalidAccountData)
+        );
+
+        let data = slot_hashes_data(&[0; MAX_ENTRIES + 1]);
+        assert_eq!(
+            SlotHashes::new(data.as_slice()).err(),
+            Some(ProgramError::InvalidAccountData)
+        );
+
+        let data = slot_hashes_data(&[0; MAX_ENTRIES]);
+        assert_eq!(SlotHashes::new(data.as_slice()).unwrap().len(), MAX_ENTRIES);
+    }
+}
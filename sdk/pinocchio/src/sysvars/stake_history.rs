@@ -0,0 +1,313 @@
+//! History of the cluster stake, per epoch.
+//!
+//! The stake history sysvar holds up to [`MAX_ENTRIES`] entries with the
+//! effective, activating and deactivating stake of an epoch, ordered from the
+//! most recent epoch to the oldest one.
+//!
+//! The account data is too large to be loaded by [`super::Sysvar::get`].
+//! Programs that receive the sysvar account can read it through a
+//! [`StakeHistory`] view, while the `fetch_*` functions read individual
+//! entries through the `sol_get_sysvar` syscall.
+
+use super::{clock::Epoch, get_sysvar_as, get_sysvar_u64};
+use crate::{
+    account_info::{AccountInfo, Ref},
+    program_error::ProgramError,
+    pubkey::Pubkey,
+};
+
+use core::{mem::size_of, ops::Deref};
+
+/// The ID of the stake history sysvar.
+pub const STAKE_HISTORY_ID: Pubkey = [
+    6, 167, 213, 23, 25, 53, 132, 208, 254, 237, 155, 179, 67, 29, 19, 32, 107, 229, 68, 40, 27,
+    87, 184, 86, 108, 197, 55, 95, 244, 0, 0, 0,
+];
+
+/// The maximum number of entries in the stake history sysvar.
+pub const MAX_ENTRIES: usize = 512;
+
+/// The size of the entry count prefix of the sysvar data.
+const NUM_ENTRIES_LEN: usize = size_of::<u64>();
+
+/// An entry of the stake history sysvar.
+///
+/// All values are stored as little-endian bytes.
+#[repr(C)]
+#[derive(Clone, Debug, Default, Eq, PartialEq)]
+pub struct StakeHistoryEntry {
+    /// The epoch of the entry.
+    epoch: [u8; 8],
+
+    /// Effective stake at this epoch.
+    effective: [u8; 8],
+
+    /// Sum of portion of activations this epoch.
+    activating: [u8; 8],
+
+    /// Sum of portion of deactivations this epoch.
+    deactivating: [u8; 8],
+}
+
+impl StakeHistoryEntry {
+    /// The length of an entry in the sysvar data.
+    pub const LEN: usize = size_of::<Self>();
+
+    /// The epoch of the entry.
+    #[inline(always)]
+    pub fn epoch(&self) -> Epoch {
+        Epoch::from_le_bytes(self.epoch)
+    }
+
+    /// Effective stake at this epoch, in lamports.
+    #[inline(always)]
+    pub fn effective(&self) -> u64 {
+        u64::from_le_bytes(self.effective)
+    }
+
+    /// Sum of portion of activations this epoch, in lamports.
+    #[inline(always)]
+    pub fn activating(&self) -> u64 {
+        u64::from_le_bytes(self.activating)
+    }
+
+    /// Sum of portion of deactivations this epoch, in lamports.
+    #[inline(always)]
+    pub fn deactivating(&self) -> u64 {
+        u64::from_le_bytes(self.deactivating)
+    }
+}
+
+/// A view over the stake history sysvar account data.
+pub struct StakeHistory<T>
+where
+    T: Deref<Target = [u8]>,
+{
+    data: T,
+}
+
+impl<T> StakeHistory<T>
+where
+    T: Deref<Target = [u8]>,
+{
+    /// Creates a new `StakeHistory` struct.
+    ///
+    /// `data` is the stake history sysvar account data.
+    ///
+    /// # Errors
+    ///
+    /// Returns [`ProgramError::AccountDataTooSmall`] if `data` is shorter than the
+    /// entries it declares, and [`ProgramError::InvalidAccountData`] if it
+    /// declares more than [`MAX_ENTRIES`] entries.
+    #[inline]
+    pub fn new(data: T) -> Result<Self, ProgramError> {
+        if data.len() < NUM_ENTRIES_LEN {
+            return Err(ProgramError::AccountDataTooSmall);
+        }
+
+        // SAFETY: The data is at least `NUM_ENTRIES_LEN` bytes long.
+        let stake_history = unsafe { Self::new_unchecked(data) };
+        let len = stake_history.len();
+
+        if len > MAX_ENTRIES {
+            return Err(ProgramError::InvalidAccountData);
+        }
+
+        // Compared by division, since `len` is read from the data.
+        if len > (stake_history.data.len() - NUM_ENTRIES_LEN) / StakeHistoryEntry::LEN {
+            return Err(ProgramError::AccountDataTooSmall);
+        }
+
+        Ok(stake_history)
+    }
+
+    /// Creates a new `StakeHistory` struct.
+    ///
+    /// `data` is the stake history sysvar account data.
+    ///
+    /// # Safety
+    ///
+    /// This function is unsafe because it does not check if the provided data is
+    /// a valid representation of the stake history sysvar data.
+    #[inline(always)]
+    pub unsafe fn new_unchecked(data: T) -> Self {
+        StakeHistory { data }
+    }
+
+    /// The number of entries.
+    #[inline(always)]
+    pub fn len(&self) -> usize {
+        // SAFETY: The first 8 bytes of the data represent the number of entries.
+        unsafe { u64::from_le_bytes(*(self.data.as_ptr() as *const [u8; 8])) as usize }
+    }
+
+    /// Indicate whether there are no entries.
+    #[inline(always)]
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+
+    /// All the entries, from the most recent epoch to the oldest one.
+    #[inline(always)]
+    pub fn entries(&self) -> &[StakeHistoryEntry] {
+        // SAFETY: The entries follow the number of entries and `StakeHistoryEntry`
+        // has an alignment of 1.
+        unsafe {
+            core::slice::from_raw_parts(
+                self.data.as_ptr().add(NUM_ENTRIES_LEN) as *const StakeHistoryEntry,
+                self.len(),
+            )
+        }
+    }
+
+    /// Get the entry at the specified index.
+    #[inline(always)]
+    pub fn get(&self, index: usize) -> Option<&StakeHistoryEntry> {
+        self.entries().get(index)
+    }
+
+    /// Get the entry of the specified epoch.
+    #[inline]
+    pub fn get_entry(&self, epoch: Epoch) -> Option<&StakeHistoryEntry> {
+        let entries = self.entries();
+        entries
+            .binary_search_by(|entry| epoch.cmp(&entry.epoch()))
+            .ok()
+            .map(|index| &entries[index])
+    }
+}
+
+impl<'a> TryFrom<&'a AccountInfo> for StakeHistory<Ref<'a, [u8]>> {
+    type Error = ProgramError;
+
+    #[inline(always)]
+    fn try_from(account_info: &'a AccountInfo) -> Result<Self, Self::Error> {
+        if account_info.key() != &STAKE_HISTORY_ID {
+            return Err(ProgramError::UnsupportedSysvar);
+        }
+
+        StakeHistory::new(account_info.try_borrow_data()?)
+    }
+}
+
+/// Fetch the number of entries of the stake history sysvar.
+#[inline]
+pub fn fetch_len() -> Result<usize, ProgramError> {
+    get_sysvar_u64(&STAKE_HISTORY_ID, 0).map(|len| len as usize)
+}
+
+/// Fetch the entry at the specified index of the stake history sysvar.
+///
+/// # Errors
+///
+/// Returns [`ProgramError::InvalidArgument`] if the index is out of bounds.
+#[inline]
+pub fn fetch_entry(index: usize) -> Result<StakeHistoryEntry, ProgramError> {
+    if index >= fetch_len()? {
+        return Err(ProgramError::InvalidArgument);
+    }
+
+    // SAFETY: `StakeHistoryEntry` is `#[repr(C)]` and any bit pattern is valid.
+    unsafe {
+        get_sysvar_as(
+            &STAKE_HISTORY_ID,
+            (NUM_ENTRIES_LEN + index * StakeHistoryEntry::LEN) as u64,
+        )
+    }
+}
+
+/// Fetch the entry of the specified epoch from the stake history sysvar.
+///
+/// The entries are binary searched, fetching one entry per step.
+#[inline]
+pub fn fetch_entry_for_epoch(epoch: Epoch) -> Result<Option<StakeHistoryEntry>, ProgramError> {
+    let (mut low, mut high) = (0, fetch_len()?);
+
+    while low < high {
+        let middle = low + (high - low) / 2;
+        // SAFETY: `StakeHistoryEntry` is `#[repr(C)]` and any bit pattern is valid.
+        let entry: StakeHistoryEntry = unsafe {
+            get_sysvar_as(
+                &STAKE_HISTORY_ID,
+                (NUM_ENTRIES_LEN + middle * StakeHistoryEntry::LEN) as u64,
+            )?
+        };
+
+        match epoch.cmp(&entry.epoch()) {
+            core::cmp::Ordering::Equal => return Ok(Some(entry)),
+            // Entries are ordered from the most recent epoch.
+            core::cmp::Ordering::Greater => high = middle,
+            core::cmp::Ordering::Less => low = middle + 1,
+        }
+    }
+
+    Ok(None)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    extern crate std;
+    use std::vec::Vec;
+
+    #[test]
+    fn test_stake_history() {
+        let epochs: [Epoch; 4] = [9, 8, 7, 5];
+        let mut data = Vec::new();
+        data.extend_from_slice(&(epochs.len() as u64).to_le_bytes());
+        for epoch in epochs {
+            data.extend_from_slice(&epoch.to_le_bytes());
+            data.extend_from_slice(&(epoch * 100).to_le_bytes());
+            data.extend_from_slice(&(epoch * 10).to_le_bytes());
+            data.extend_from_slice(&epoch.to_le_bytes());
+        }
+
+        let stake_history = StakeHistory::new(data.as_slice()).unwrap();
+
+        assert_eq!(stake_history.len(), 4);
+        assert_eq!(stake_history.get(3).map(StakeHistoryEntry::epoch), Some(5));
+        assert!(stake_history.get(4).is_none());
+
+        for epoch in epochs {
+            let entry = stake_history.get_entry(epoch).unwrap();
+            assert_eq!(entry.epoch(), epoch);
+            assert_eq!(entry.effective(), epoch * 100);
+            assert_eq!(entry.activating(), epoch * 10);
+            assert_eq!(entry.deactivating(), epoch);
+        }
+
+        for epoch in [0, 6, 10] {
+            assert!(stake_history.get_entry(epoch).is_none());
+        }
+
+        assert_eq!(
+            StakeHistory::new(&data[..data.len() - 1]).err(),
+            Some(ProgramError::AccountDataTooSmall)
+        );
+    }
+
+    #[test]
+    fn test_stake_history_invalid_len() {
+        // `32 * 2^59` wraps to `0`.
+        let mut data = [0u8; 16];
+        data[..8].copy_from_slice(&(1u64 << 59).to_le_bytes());
+        assert_eq!(
+            StakeHistory::new(data.as_slice()).err(),
+            Some(ProgramError::InvalidAccountData)
+        );
+
+        let mut data = [0u8; NUM_ENTRIES_LEN + (MAX_ENTRIES + 1) * StakeHistoryEntry::LEN];
+        data[..8].copy_from_slice(&(MAX_ENTRIES as u64 + 1).to_le_bytes());
+        assert_eq!(
+            StakeHistory::new(data.as_slice()).err(),
+            Some(ProgramError::InvalidAccountData)
+        );
+
+        data[..8].copy_from_slice(&(MAX_ENTRIES as u64).to_le_bytes());
+        assert_eq!(
+            StakeHistory::new(data.as_slice()).unwrap().len(),
+            MAX_ENTRIES
+        );
+    }
+}
@@ -0,0 +1,140 @@
+//! Compute unit accounting of the in-process runtime.
+//!
+//! The syscall wrappers of `pinocchio` charge the compute units of the
+//! corresponding syscall to a meter of the current thread, using the default
+//! costs of the runtime compute budget. Cross-program invocations charge the
+//! invocation cost to the same meter, and the invoked program charges its own
+//! syscalls, so [`compute_units_consumed`] reports the total of a test:
+//!
+//! ```
+//! use pinocchio::{log::sol_log, testing::compute_budget};
+//!
+//! compute_budget::reset();
+//! sol_log("hello");
+//!
+//! assert_eq!(compute_budget::compute_units_consumed(), compute_budget::SYSCALL_BASE_COST);
+//! ```
+//!
+//! Only syscalls are charged: the units consumed by the instructions of the
+//! program itself are not modelled on the host. The meter is not enforced
+//! either; [`remaining_compute_units`] saturates at `0` once the limit is
+//! reached.
+//!
+//! [`remaining_compute_units`]: crate::compute_units::remaining_compute_units
+
+use core::cell::Cell;
+
+/// Maximum compute units a transaction may request.
+pub const MAX_COMPUTE_UNIT_LIMIT: u64 = 1_400_000;
+
+/// Base cost of a syscall.
+pub const SYSCALL_BASE_COST: u64 = 100;
+
+/// Cost of logging five `u64` values.
+pub const LOG_64_UNITS: u64 = 100;
+
+/// Cost of logging a `Pubkey`.
+pub const LOG_PUBKEY_UNITS: u64 = 100;
+
+/// Base cost of a cross-program invocation.
+pub const INVOKE_UNITS: u64 = 1_000;
+
+/// Number of bytes copied per compute unit by cross-program invocations,
+/// return data and memory operations.
+pub const CPI_BYTES_PER_UNIT: u64 = 250;
+
+/// Base cost of the SHA-256, Keccak-256 and BLAKE3 syscalls.
+pub const SHA256_BASE_COST: u64 = 85;
+
+/// Cost per two bytes hashed by the SHA-256, Keccak-256 and BLAKE3 syscalls.
+pub const SHA256_BYTE_COST: u64 = 1;
+
+/// Cost of deriving a program address.
+pub const CREATE_PROGRAM_ADDRESS_UNITS: u64 = 1_500;
+
+/// Minimum cost of a memory operation.
+pub const MEM_OP_BASE_COST: u64 = 10;
+
+std::thread_local! {
+    static CONSUMED: Cell<u64> = const { Cell::new(0) };
+
+    static LIMIT: Cell<u64> = const { Cell::new(MAX_COMPUTE_UNIT_LIMIT) };
+}
+
+/// Return the compute units consumed on the current thread since the last
+/// [`reset`].
+pub fn compute_units_consumed() -> u64 {
+    CONSUMED.with(Cell::get)
+}
+
+/// Set the compute unit limit used to compute the remaining compute units on
+/// the current thread.
+///
+/// The limit is [`MAX_COMPUTE_UNIT_LIMIT`] by default.
+pub fn set_compute_unit_limit(limit: u64) {
+    LIMIT.with(|cell| cell.set(limit));
+}
+
+/// Reset the compute units consumed on the current thread.
+pub fn reset() {
+    CONSUMED.with(|cell| cell.set(0));
+}
+
+/// Return the compute units left before the limit is reached.
+pub(crate) fn remaining() -> u64 {
+    LIMIT
+        .with(Cell::get)
+        .saturating_sub(compute_units_consumed())
+}
+
+/// Charge `units` to the meter of the current thread.
+pub(crate) fn consume(units: u64) {
+    CONSUMED.with(|cell| cell.set(cell.get().saturating_add(units)));
+}
+
+/// Charge the cost of hashing `vals`.
+#[cfg(feature = "hash")]
+pub(crate) fn consume_hash(vals: &[&[u8]]) {
+    consume(SHA256_BASE_COST);
+    vals.iter()
+        .for_each(|val| consume(MEM_OP_BASE_COST.max(SHA256_BYTE_COST * (val.len() as u64 / 2))));
+}
+
+/// Charge the cost of a memory operation on `n` bytes.
+pub(crate) fn consume_mem_op(n: usize) {
+    consume(MEM_OP_BASE_COST.max(n as u64 / CPI_BYTES_PER_UNIT));
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{compute_units::remaining_compute_units, log, pubkey};
+
+    #[test]
+    fn test_consume() {
+        reset();
+        set_compute_unit_limit(1_000);
+
+        log::sol_log_64(1, 2, 3, 4, 5);
+        pubkey::log(&[1; 32]);
+        log::sol_log_data(&[&[0; 50], &[0; 50]]);
+        assert_eq!(compute_units_consumed(), 100 + 100 + (100 + 2 * 100 + 100));
+
+        reset();
+        unsafe { crate::memory::sol_memset(&mut [0; 1_000], 1, 1_000) };
+        assert_eq!(compute_units_consumed(), 10);
+
+        assert_eq!(remaining_compute_units(), 1_000 - 10 - 100);
+        set_compute_unit_limit(0);
+        assert_eq!(remaining_compute_units(), 0);
+        set_compute_unit_limit(MAX_COMPUTE_UNIT_LIMIT);
+    }
+
+    #[cfg(feature = "hash")]
+    #[test]
+    fn test_consume_hash() {
+        reset();
+        crate::hash::sha256(&[&[0; 100], &[0; 4]]);
+        assert_eq!(compute_units_consumed(), 85 + 50 + 10);
+    }
+}
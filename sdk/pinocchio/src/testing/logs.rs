@@ -0,0 +1,97 @@
+//! Program logs of the in-process runtime.
+//!
+//! The logging functions of `pinocchio` record their input on the current
+//! thread, so tests can assert on the logs and events emitted by a program
+//! instead of parsing its output:
+//!
+//! ```
+//! use pinocchio::{log::sol_log_data, msg, testing::logs::{self, Log}};
+//!
+//! logs::reset();
+//! msg!("transfer");
+//! sol_log_data(&[b"event", &[1, 2, 3]]);
+//!
+//! assert_eq!(
+//!     logs::take(),
+//!     [
+//!         Log::Message("transfer".into()),
+//!         Log::Data(vec![b"event".to_vec(), vec![1, 2, 3]]),
+//!     ]
+//! );
+//! ```
+
+use std::{cell::RefCell, string::String, vec::Vec};
+
+use crate::pubkey::Pubkey;
+
+/// Entry of the program logs.
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub enum Log {
+    /// Message logged by [`sol_log`](crate::log::sol_log).
+    Message(String),
+
+    /// Values logged by [`sol_log_64`](crate::log::sol_log_64).
+    U64([u64; 5]),
+
+    /// Fields logged by [`sol_log_data`](crate::log::sol_log_data).
+    Data(Vec<Vec<u8>>),
+
+    /// Key logged by [`pubkey::log`](crate::pubkey::log).
+    Pubkey(Pubkey),
+
+    /// Remaining compute units logged by
+    /// [`sol_log_compute_units`](crate::log::sol_log_compute_units).
+    ComputeUnits(u64),
+}
+
+std::thread_local! {
+    static LOGS: RefCell<Vec<Log>> = const { RefCell::new(Vec::new()) };
+}
+
+/// Return the logs recorded on the current thread since the last [`reset`].
+pub fn recorded() -> Vec<Log> {
+    LOGS.with(|logs| logs.borrow().clone())
+}
+
+/// Return and remove the logs recorded on the current thread.
+pub fn take() -> Vec<Log> {
+    LOGS.with(|logs| core::mem::take(&mut *logs.borrow_mut()))
+}
+
+/// Remove the logs recorded on the current thread.
+pub fn reset() {
+    LOGS.with(|logs| logs.borrow_mut().clear());
+}
+
+/// Record `log` on the current thread.
+pub(crate) fn record(log: Log) {
+    LOGS.with(|logs| logs.borrow_mut().push(log));
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{log, pubkey};
+
+    #[test]
+    fn test_record() {
+        reset();
+
+        log::sol_log_64(1, 2, 3, 4, 5);
+        pubkey::log(&[7; 32]);
+        log::sol_log_params(&[], &[9]);
+        assert_eq!(
+            recorded(),
+            [
+                Log::U64([1, 2, 3, 4, 5]),
+                Log::Pubkey([7; 32]),
+                Log::Message("Instruction data".into()),
+                Log::U64([0, 0, 0, 0, 9]),
+            ]
+        );
+
+        log::sol_log_compute_units();
+        assert!(matches!(take().last(), Some(Log::ComputeUnits(_))));
+        assert!(recorded().is_empty());
+    }
+}
@@ -0,0 +1,881 @@
+//! In-process runtime for host-side tests.
+//!
+//! On non-`solana` targets there is no runtime to execute cross-program
+//! invocations. This module provides a minimal one: tests register a handler
+//! per program id with [`register_program`], and [`process_instruction`]
+//! invokes a program the way the runtime invokes a top-level instruction.
+//! Cross-program invocations issued by the program through the [`cpi`]
+//! functions are then dispatched to the registered handlers, which receive
+//! the same account memory as the caller.
+//!
+//! Like the runtime, cross-program invocations:
+//!
+//! * fail with [`ProgramError::MissingRequiredSignature`] when an account is
+//!   a signer of the instruction, but neither a signer of the caller nor a
+//!   program derived address of the caller signed with [`Signer`] seeds, or
+//!   when [`Signer`] seeds are passed outside of [`process_instruction`];
+//! * fail with [`ProgramError::Immutable`] when an account is writable for
+//!   the instruction but not for the caller, or when the invoked program
+//!   modifies an account it received as read-only;
+//! * fail with [`ProgramError::InvalidArgument`] when invoked from an
+//!   instruction at [`cpi::MAX_INVOKE_STACK_HEIGHT`], where the runtime fails
+//!   with a call depth error;
+//! * leave the accounts unchanged when the invoked program fails, since a
+//!   failed invocation aborts the transaction on-chain;
+//! * maintain the stack height returned by [`cpi::get_stack_height`] and the
+//!   return data of [`cpi::get_return_data`].
+//!
+//! The runtime state is stored per thread, so tests running in parallel do
+//! not observe each other's programs. Each thread also meters the compute
+//! units charged by the syscall wrappers and cross-program invocations, see
+//! [`compute_budget`], and records the program logs, see [`logs`].
+//!
+//! ```
+//! use pinocchio::{
+//!     account_info::AccountInfo,
+//!     cpi::invoke,
+//!     entrypoint::serialize::{InputAccount, InputBuilder},
+//!     instruction::{AccountMeta, Instruction},
+//!     program_error::ProgramError,
+//!     pubkey::Pubkey,
+//!     testing::{self, InputAccounts},
+//!     ProgramResult,
+//! };
+//!
+//! const CALLER: Pubkey = [1; 32];
+//! const CALLEE: Pubkey = [2; 32];
+//!
+//! fn caller(_: &Pubkey, accounts: &[AccountInfo], _: &[u8]) -> ProgramResult {
+//!     let instruction = Instruction {
+//!         program_id: &CALLEE,
+//!         accounts: &[AccountMeta::writable_signer(accounts[0].key())],
+//!         data: &[],
+//!     };
+//!     invoke(&instruction, &[&accounts[0]])
+//! }
+//!
+//! fn callee(_: &Pubkey, accounts: &[AccountInfo], _: &[u8]) -> ProgramResult {
+//!     *accounts[0].try_borrow_mut_lamports()? += 1;
+//!     Ok(())
+//! }
+//!
+//! testing::register_program(CALLER, caller);
+//! testing::register_program(CALLEE, callee);
+//!
+//! let accounts = InputAccounts::new(
+//!     InputBuilder::new(CALLER)
+//!         .account(InputAccount::new([3; 32], 10, [0; 32]).signer().writable())
+//!         .build(),
+//! );
+//!
+//! testing::process_instruction(&CALLER, &accounts, &[]).unwrap();
+//!
+//! assert_eq!(accounts[0].lamports(), 11);
+//! ```
+//!
+//! [`cpi`]: crate::cpi
+//! [`cpi::MAX_INVOKE_STACK_HEIGHT`]: crate::cpi::MAX_INVOKE_STACK_HEIGHT
+//! [`cpi::get_stack_height`]: crate::cpi::get_stack_height
+//! [`cpi::get_return_data`]: crate::cpi::get_return_data
+
+pub mod compute_budget;
+pub mod logs;
+
+use core::{mem::MaybeUninit, ops::Deref};
+use std::{cell::RefCell, collections::HashMap, vec::Vec};
+
+use crate::{
+    account_info::AccountInfo,
+    cpi::{
+        get_stack_height, reset_stack_height, set_stack_height, MAX_INVOKE_STACK_HEIGHT,
+        MAX_RETURN_DATA, TRANSACTION_LEVEL_STACK_HEIGHT,
+    },
+    entrypoint::{deserialize, serialize::Input},
+    epoch_stake::clear_epoch_stakes,
+    instruction::{Account, Instruction, Signer},
+    program_error::ProgramError,
+    pubkey::{derive_program_address, Pubkey},
+    ProgramResult, MAX_TX_ACCOUNTS,
+};
+
+/// Handler of the instructions of a program.
+///
+/// This is the signature of the `process_instruction` function passed to the
+/// entrypoint macros.
+pub type ProcessInstruction = fn(&Pubkey, &[AccountInfo], &[u8]) -> ProgramResult;
+
+/// State of the runtime of the current thread.
+#[derive(Default)]
+struct Runtime {
+    /// Registered programs.
+    programs: HashMap<Pubkey, ProcessInstruction>,
+
+    /// Programs being processed, from the top-level one.
+    stack: Vec<Pubkey>,
+
+    /// Program that set the return data, and the data.
+    return_data: (Pubkey, Vec<u8>),
+
+    /// Instructions issued by the programs registered with [`process_invoke`].
+    invokes: HashMap<Pubkey, Invoke>,
+}
+
+std::thread_local! {
+    static RUNTIME: RefCell<Runtime> = RefCell::new(Runtime::default());
+}
+
+/// Register the handler of the instructions of `program_id` on the current
+/// thread.
+pub fn register_program(program_id: Pubkey, processor: ProcessInstruction) {
+    RUNTIME.with(|runtime| runtime.borrow_mut().programs.insert(program_id, processor));
+}
+
+/// Remove the registered programs, the return data, the logs and the epoch
+/// stakes of the current thread, and reset its compute units consumed.
+pub fn reset() {
+    RUNTIME.with(|runtime| *runtime.borrow_mut() = Runtime::default());
+    compute_budget::reset();
+    logs::reset();
+    clear_epoch_stakes();
+    reset_stack_height();
+}
+
+/// Process an instruction of `program_id` as a top-level instruction.
+///
+/// # Errors
+///
+/// Returns [`ProgramError::IncorrectProgramId`] if `program_id` is not
+/// registered, or the error returned by the program.
+///
+/// # Panics
+///
+/// Panics if called while a program is being processed.
+pub fn process_instruction(
+    program_id: &Pubkey,
+    accounts: &[AccountInfo],
+    instruction_data: &[u8],
+) -> ProgramResult {
+    let processor = RUNTIME.with(|runtime| {
+        let mut runtime = runtime.borrow_mut();
+        assert!(
+            runtime.stack.is_empty(),
+            "process_instruction called while processing an instruction"
+        );
+        runtime.return_data = Default::default();
+        runtime.programs.get(program_id).copied()
+    });
+
+    let processor = processor.ok_or(ProgramError::IncorrectProgramId)?;
+
+    set_stack_height(TRANSACTION_LEVEL_STACK_HEIGHT);
+    let result = execute(program_id, processor, accounts, instruction_data);
+    set_stack_height(TRANSACTION_LEVEL_STACK_HEIGHT);
+
+    result
+}
+
+/// Accounts deserialized from a serialized program [`Input`].
+///
+/// The accounts reference the memory of the input, which is kept alive with
+/// them. They dereference to the slice passed to [`process_instruction`].
+pub struct InputAccounts {
+    accounts: Vec<AccountInfo>,
+
+    /// Memory referenced by the accounts.
+    _input: Input,
+}
+
+impl InputAccounts {
+    /// Deserialize the accounts of `input`, including duplicated accounts.
+    pub fn new(mut input: Input) -> Self {
+        let mut accounts = [const { MaybeUninit::<AccountInfo>::uninit() }; MAX_TX_ACCOUNTS];
+        // SAFETY: `input` was serialized in the runtime format by the input
+        // builder, and its buffer does not move when `input` is moved.
+        let (_, count, _) =
+            unsafe { deserialize::<MAX_TX_ACCOUNTS>(input.as_mut_ptr(), &mut accounts) };
+        let accounts = accounts[..count]
+            .iter()
+            // SAFETY: `deserialize` has initialized the first `count` accounts.
+            .map(|account| unsafe { account.assume_init_read() })
+            .collect();
+
+        Self {
+            accounts,
+            _input: input,
+        }
+    }
+}
+
+impl From<Input> for InputAccounts {
+    fn from(input: Input) -> Self {
+        Self::new(input)
+    }
+}
+
+impl Deref for InputAccounts {
+    type Target = [AccountInfo];
+
+    fn deref(&self) -> &Self::Target {
+        &self.accounts
+    }
+}
+
+/// Instructions issued by a program registered with [`process_invoke`].
+pub type Invoke = fn(&[AccountInfo]) -> ProgramResult;
+
+/// Process a top-level instruction of a program `program_id` that calls
+/// `invoke` with its accounts.
+///
+/// This is a shorthand to test cross-program invocations without writing a
+/// calling program: `invoke` typically issues instructions with the [`cpi`]
+/// functions.
+///
+/// # Errors
+///
+/// Returns the error returned by `invoke`.
+///
+/// [`cpi`]: crate::cpi
+pub fn process_invoke(
+    program_id: &Pubkey,
+    invoke: Invoke,
+    accounts: &[AccountInfo],
+) -> ProgramResult {
+    fn program(program_id: &Pubkey, accounts: &[AccountInfo], _: &[u8]) -> ProgramResult {
+        // The program is only registered together with its `invoke`.
+        let invoke = RUNTIME.with(|runtime| runtime.borrow().invokes[program_id]);
+        invoke(accounts)
+    }
+
+    RUNTIME.with(|runtime| runtime.borrow_mut().invokes.insert(*program_id, invoke));
+    register_program(*program_id, program);
+
+    process_instruction(program_id, accounts, &[])
+}
+
+/// Reader of serialized instruction data.
+///
+/// Program emulators use it to decode the fixed-size little-endian fields of
+/// their instructions. Each read fails with the error passed to
+/// [`InstructionData::new`] when the data is too short.
+pub struct InstructionData<'a> {
+    data: &'a [u8],
+    error: ProgramError,
+}
+
+impl<'a> InstructionData<'a> {
+    /// Create a reader of `data` that fails with `error`.
+    pub fn new(data: &'a [u8], error: ProgramError) -> Self {
+        Self { data, error }
+    }
+
+    /// Read the next `len` bytes.
+    pub fn slice(&mut self, len: usize) -> Result<&'a [u8], ProgramError> {
+        if self.data.len() < len {
+            return Err(self.error.clone());
+        }
+        let (bytes, rest) = self.data.split_at(len);
+        self.data = rest;
+        Ok(bytes)
+    }
+
+    /// Read the next `N` bytes.
+    pub fn bytes<const N: usize>(&mut self) -> Result<[u8; N], ProgramError> {
+        let (bytes, rest) = self
+            .data
+            .split_first_chunk::<N>()
+            .ok_or_else(|| self.error.clone())?;
+        self.data = rest;
+        Ok(*bytes)
+    }
+
+    /// Read a `u8`.
+    pub fn u8(&mut self) -> Result<u8, ProgramError> {
+        self.bytes().map(|[value]| value)
+    }
+
+    /// Read a little-endian `u32`.
+    pub fn u32(&mut self) -> Result<u32, ProgramError> {
+        self.bytes().map(u32::from_le_bytes)
+    }
+
+    /// Read a little-endian `u64`.
+    pub fn u64(&mut self) -> Result<u64, ProgramError> {
+        self.bytes().map(u64::from_le_bytes)
+    }
+
+    /// Read a public key.
+    pub fn pubkey(&mut self) -> Result<Pubkey, ProgramError> {
+        self.bytes()
+    }
+}
+
+/// Run `processor` with `program_id` pushed on the stack.
+fn execute(
+    program_id: &Pubkey,
+    processor: ProcessInstruction,
+    accounts: &[AccountInfo],
+    instruction_data: &[u8],
+) -> ProgramResult {
+    RUNTIME.with(|runtime| runtime.borrow_mut().stack.push(*program_id));
+    let result = processor(program_id, accounts, instruction_data);
+    RUNTIME.with(|runtime| runtime.borrow_mut().stack.pop());
+
+    result
+}
+
+/// Return the program being processed, if any.
+fn current_program() -> Option<Pubkey> {
+    RUNTIME.with(|runtime| runtime.borrow().stack.last().copied())
+}
+
+/// Snapshot of an account passed to an invoked program.
+struct AccountSnapshot {
+    account: AccountInfo,
+    lamports: u64,
+    owner: Pubkey,
+    data: Vec<u8>,
+}
+
+impl AccountSnapshot {
+    fn new(account: AccountInfo) -> Self {
+        // SAFETY: The account is only read to be compared or restored after
+        // the invocation.
+        let (owner, data) = unsafe { (*account.owner(), account.borrow_data_unchecked().to_vec()) };
+        Self {
+            lamports: account.lamports(),
+            owner,
+            data,
+            account,
+        }
+    }
+
+    fn is_modified(&self) -> bool {
+        // SAFETY: The invoked program has returned.
+        unsafe {
+            self.account.lamports() != self.lamports
+                || *self.account.owner() != self.owner
+                || self.account.borrow_data_unchecked() != self.data.as_slice()
+        }
+    }
+
+    /// Restore the lamports, owner and data of the account.
+    fn restore(&self) {
+        // SAFETY: The invoked program has returned, and the data length is
+        // restored to a length the account had before the invocation.
+        unsafe {
+            *self.account.borrow_mut_lamports_unchecked() = self.lamports;
+            self.account.assign(&self.owner);
+            (*self.account.raw).data_len = self.data.len() as u64;
+            self.account
+                .borrow_mut_data_unchecked()
+                .copy_from_slice(&self.data);
+        }
+    }
+}
+
+/// Dispatch a cross-program invocation to the registered program.
+///
+/// The `accounts` are the validated accounts of [`crate::cpi`] functions.
+pub(crate) fn invoke_signed(
+    instruction: &Instruction,
+    accounts: &[Account],
+    signers_seeds: &[Signer],
+) -> ProgramResult {
+    let caller = current_program();
+
+    let stack_height = get_stack_height();
+    if stack_height >= MAX_INVOKE_STACK_HEIGHT {
+        return Err(ProgramError::InvalidArgument);
+    }
+
+    compute_budget::consume(compute_budget::INVOKE_UNITS);
+    compute_budget::consume(instruction.data.len() as u64 / compute_budget::CPI_BYTES_PER_UNIT);
+
+    let mut signers = Vec::with_capacity(signers_seeds.len());
+    for signer in signers_seeds {
+        // Signer seeds are derived from the program id of the caller, so they
+        // cannot sign outside of `process_instruction`.
+        let caller = caller.ok_or(ProgramError::MissingRequiredSignature)?;
+        // SAFETY: A `Signer` references `len` seeds.
+        let seeds = unsafe { core::slice::from_raw_parts(signer.seeds, signer.len as usize) };
+        let seeds = seeds.iter().map(|seed| &**seed).collect::<Vec<&[u8]>>();
+        signers.push(derive_program_address(&seeds, &caller)?);
+    }
+
+    // Accounts of the invoked instruction, with the privileges of the caller.
+    let mut callee_accounts = Vec::with_capacity(instruction.accounts.len());
+    for meta in instruction.accounts {
+        let account = accounts
+            .iter()
+            .map(|account| AccountInfo { raw: account.raw() })
+            .find(|account| account.key() == meta.pubkey)
+            .ok_or(ProgramError::NotEnoughAccountKeys)?;
+        compute_budget::consume(account.data_len() as u64 / compute_budget::CPI_BYTES_PER_UNIT);
+        callee_accounts.push(account);
+    }
+
+    // Privileges of each account for the invoked instruction, in order of the
+    // first occurrence of the account.
+    let mut privileges: Vec<(AccountInfo, bool, bool)> = Vec::new();
+    for (account, meta) in callee_accounts.iter().zip(instruction.accounts) {
+        match privileges.iter_mut().find(|(other, _, _)| other == account) {
+            Some((_, is_signer, is_writable)) => {
+                *is_signer |= meta.is_signer;
+                *is_writable |= meta.is_writable;
+            }
+            None => privileges.push((account.clone(), meta.is_signer, meta.is_writable)),
+        }
+    }
+
+    for (account, is_signer, is_writable) in &privileges {
+        if *is_signer && !account.is_signer() && !signers.contains(account.key()) {
+            return Err(ProgramError::MissingRequiredSignature);
+        }
+        if *is_writable && !account.is_writable() {
+            return Err(ProgramError::Immutable);
+        }
+    }
+
+    let processor = RUNTIME
+        .with(|runtime| {
+            runtime
+                .borrow()
+                .programs
+                .get(instruction.program_id)
+                .copied()
+        })
+        .ok_or(ProgramError::IncorrectProgramId)?;
+
+    let snapshots = privileges
+        .iter()
+        .map(|(account, _, is_writable)| (AccountSnapshot::new(account.clone()), *is_writable))
+        .collect::<Vec<_>>();
+
+    // The invoked program sees the accounts with the privileges of the
+    // instruction; the privileges of the caller are restored afterwards.
+    let caller_privileges = privileges
+        .iter()
+        .map(|(account, is_signer, is_writable)| unsafe {
+            let raw = &mut *account.raw;
+            let privileges = (raw.is_signer, raw.is_writable);
+            raw.is_signer = *is_signer as u8;
+            raw.is_writable = *is_writable as u8;
+            privileges
+        })
+        .collect::<Vec<_>>();
+
+    RUNTIME.with(|runtime| runtime.borrow_mut().return_data = Default::default());
+
+    set_stack_height(stack_height + 1);
+    let result = execute(
+        instruction.program_id,
+        processor,
+        &callee_accounts,
+        instruction.data,
+    );
+    set_stack_height(stack_height);
+
+    for ((account, _, _), (is_signer, is_writable)) in privileges.iter().zip(caller_privileges) {
+        // SAFETY: The invoked program has returned.
+        unsafe {
+            (*account.raw).is_signer = is_signer;
+            (*account.raw).is_writable = is_writable;
+        }
+    }
+
+    let result = result.and_then(|()| {
+        if snapshots
+            .iter()
+            .any(|(snapshot, is_writable)| !is_writable && snapshot.is_modified())
+        {
+            Err(ProgramError::Immutable)
+        } else {
+            Ok(())
+        }
+    });
+
+    // A failed invocation aborts the transaction on-chain, so its changes are
+    // never observed; the accounts are restored for the caller.
+    if result.is_err() {
+        snapshots
+            .iter()
+            .for_each(|(snapshot, _)| snapshot.restore());
+    }
+
+    result
+}
+
+/// Set the return data of the program being processed.
+pub(crate) fn set_return_data(data: &[u8]) {
+    assert!(
+        data.len() <= MAX_RETURN_DATA,
+        "return data too large: {} > {}",
+        data.len(),
+        MAX_RETURN_DATA
+    );
+    compute_budget::consume(
+        compute_budget::SYSCALL_BASE_COST + data.len() as u64 / compute_budget::CPI_BYTES_PER_UNIT,
+    );
+    let program_id = current_program().unwrap_or_default();
+    RUNTIME.with(|runtime| runtime.borrow_mut().return_data = (program_id, data.to_vec()));
+}
+
+/// Return the program that set the return data, and the data.
+pub(crate) fn get_return_data() -> (Pubkey, Vec<u8>) {
+    let return_data = RUNTIME.with(|runtime| runtime.borrow().return_data.clone());
+    compute_budget::consume(
+        compute_budget::SYSCALL_BASE_COST
+            + return_data.1.len() as u64 / compute_budget::CPI_BYTES_PER_UNIT,
+    );
+    return_data
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{
+        cpi::{get_return_data, invoke, invoke_signed, set_return_data},
+        entrypoint::serialize::{InputAccount, InputBuilder},
+        instruction::AccountMeta,
+        pubkey::find_program_address,
+        seeds,
+    };
+
+    const CALLER: Pubkey = [1; 32];
+
+    const CALLEE: Pubkey = [2; 32];
+
+    /// Transfer one lamport from the first account to the second one.
+    fn callee(_: &Pubkey, accounts: &[AccountInfo], data: &[u8]) -> ProgramResult {
+        if !accounts[0].is_signer() {
+            return Err(ProgramError::MissingRequiredSignature);
+        }
+        *accounts[0].try_borrow_mut_lamports()? -= 1;
+        *accounts[1].try_borrow_mut_lamports()? += 1;
+
+        set_return_data(&[get_stack_height() as u8]);
+
+        if data == [1] {
+            Err(ProgramError::Custom(1))
+        } else {
+            Ok(())
+        }
+    }
+
+    /// Invoke the callee with the first two accounts, and the third account
+    /// as the program derived address signer, if present.
+    fn caller(_: &Pubkey, accounts: &[AccountInfo], data: &[u8]) -> ProgramResult {
+        let instruction = Instruction {
+            program_id: &CALLEE,
+            accounts: &[
+                AccountMeta::writable_signer(accounts[0].key()),
+                AccountMeta::writable(accounts[1].key()),
+            ],
+            data,
+        };
+
+        if accounts.len() > 2 {
+            let (_, bump) = find_program_address(&[b"vault"], &CALLER);
+            let bump = [bump];
+            invoke_signed(
+                &instruction,
+                &[&accounts[0], &accounts[1]],
+                &[seeds!(b"vault", &bump).as_slice().into()],
+            )?;
+        } else {
+            invoke(&instruction, &[&accounts[0], &accounts[1]])?;
+        }
+
+        let return_data = get_return_data().unwrap();
+        assert_eq!(return_data.program_id(), &CALLEE);
+        assert_eq!(return_data.as_slice(), &[2]);
+
+        Ok(())
+    }
+
+    fn register() {
+        reset();
+        register_program(CALLER, caller);
+        register_program(CALLEE, callee);
+    }
+
+    #[test]
+    fn test_invoke() {
+        register();
+
+        let accounts = InputAccounts::new(
+            InputBuilder::new(CALLER)
+                .account(InputAccount::new([3; 32], 10, [0; 32]).signer().writable())
+                .account(InputAccount::new([4; 32], 0, [0; 32]).writable())
+                .build(),
+        );
+
+        assert_eq!(process_instruction(&CALLER, &accounts, &[]), Ok(()));
+        assert_eq!(accounts[0].lamports(), 9);
+        assert_eq!(accounts[1].lamports(), 1);
+
+        // The privileges of the caller are restored.
+        assert!(!accounts[1].is_signer());
+        assert_eq!(get_stack_height(), TRANSACTION_LEVEL_STACK_HEIGHT);
+
+        // The changes of a failed invocation are discarded.
+        assert_eq!(
+            process_instruction(&CALLER, &accounts, &[1]),
+            Err(ProgramError::Custom(1))
+        );
+        assert_eq!(accounts[0].lamports(), 9);
+        assert_eq!(accounts[1].lamports(), 1);
+        assert_eq!(
+            process_instruction(&CALLEE, &[accounts[1].clone(), accounts[0].clone()], &[]),
+            Err(ProgramError::MissingRequiredSignature)
+        );
+        assert_eq!(
+            process_instruction(&[5; 32], &accounts, &[]),
+            Err(ProgramError::IncorrectProgramId)
+        );
+    }
+
+    #[test]
+    fn test_invoke_privileges() {
+        register();
+
+        let accounts = InputAccounts::new(
+            InputBuilder::new(CALLER)
+                .account(InputAccount::new([3; 32], 10, [0; 32]).writable())
+                .account(InputAccount::new([4; 32], 0, [0; 32]).writable())
+                .build(),
+        );
+
+        assert_eq!(
+            process_instruction(&CALLER, &accounts, &[]),
+            Err(ProgramError::MissingRequiredSignature)
+        );
+
+        let accounts = InputAccounts::new(
+            InputBuilder::new(CALLER)
+                .account(InputAccount::new([3; 32], 10, [0; 32]).signer().writable())
+                .account(InputAccount::new([4; 32], 0, [0; 32]))
+                .build(),
+        );
+
+        assert_eq!(
+            process_instruction(&CALLER, &accounts, &[]),
+            Err(ProgramError::Immutable)
+        );
+        assert_eq!(accounts[0].lamports(), 10);
+    }
+
+    #[test]
+    fn test_invoke_signed() {
+        register();
+
+        let (vault, _) = find_program_address(&[b"vault"], &CALLER);
+
+        let accounts = InputAccounts::new(
+            InputBuilder::new(CALLER)
+                .account(InputAccount::new(vault, 10, CALLER).writable())
+                .account(InputAccount::new([4; 32], 0, [0; 32]).writable())
+                .account(InputAccount::new([5; 32], 0, [0; 32]))
+                .build(),
+        );
+
+        assert_eq!(process_instruction(&CALLER, &accounts, &[]), Ok(()));
+        assert_eq!(accounts[0].lamports(), 9);
+        assert!(!accounts[0].is_signer());
+
+        // The seeds must derive the signer from the caller program id.
+        register_program([6; 32], caller);
+        assert_eq!(
+            process_instruction(&[6; 32], &accounts, &[]),
+            Err(ProgramError::MissingRequiredSignature)
+        );
+    }
+
+    #[test]
+    fn test_invoke_signed_without_caller() {
+        register();
+
+        let (vault, bump) = find_program_address(&[b"vault"], &CALLER);
+        let bump = [bump];
+
+        let accounts = InputAccounts::new(
+            InputBuilder::new(CALLER)
+                .account(InputAccount::new(vault, 10, CALLER).writable())
+                .account(InputAccount::new([4; 32], 0, [0; 32]).writable())
+                .build(),
+        );
+        let instruction = Instruction {
+            program_id: &CALLEE,
+            accounts: &[
+                AccountMeta::writable_signer(accounts[0].key()),
+                AccountMeta::writable(accounts[1].key()),
+            ],
+            data: &[],
+        };
+
+        assert_eq!(
+            invoke_signed(
+                &instruction,
+                &[&accounts[0], &accounts[1]],
+                &[seeds!(b"vault", &bump).as_slice().into()],
+            ),
+            Err(ProgramError::MissingRequiredSignature)
+        );
+        assert_eq!(accounts[0].lamports(), 10);
+    }
+
+    #[test]
+    fn test_invoke_max_stack_height() {
+        /// Invoke itself with the first account until it fails.
+        fn recurse(program_id: &Pubkey, accounts: &[AccountInfo], _: &[u8]) -> ProgramResult {
+            *accounts[0].try_borrow_mut_lamports()? += 1;
+            let instruction = Instruction {
+                program_id,
+                accounts: &[AccountMeta::writable(accounts[0].key())],
+                data: &[],
+            };
+            invoke(&instruction, &[&accounts[0]])
+        }
+
+        reset();
+        register_program(CALLER, recurse);
+
+        let accounts = InputAccounts::new(
+            InputBuilder::new(CALLER)
+                .account(InputAccount::new([3; 32], 0, [0; 32]).writable())
+                .build(),
+        );
+
+        assert_eq!(
+            process_instruction(&CALLER, &accounts, &[]),
+            Err(ProgramError::InvalidArgument)
+        );
+        // Only the change of the top-level instruction is kept.
+        assert_eq!(accounts[0].lamports(), 1);
+        assert_eq!(get_stack_height(), TRANSACTION_LEVEL_STACK_HEIGHT);
+    }
+
+    #[test]
+    fn test_process_invoke() {
+        fn transfer(accounts: &[AccountInfo]) -> ProgramResult {
+            let instruction = Instruction {
+                program_id: &CALLEE,
+                accounts: &[
+                    AccountMeta::writable_signer(accounts[0].key()),
+                    AccountMeta::writable(accounts[1].key()),
+                ],
+                data: &[],
+            };
+            invoke(&instruction, &[&accounts[0], &accounts[1]])
+        }
+
+        reset();
+        register_program(CALLEE, callee);
+
+        let accounts = InputAccounts::new(
+            InputBuilder::new(CALLER)
+                .account(InputAccount::new([3; 32], 10, [0; 32]).signer().writable())
+                .account(InputAccount::new([4; 32], 0, [0; 32]).writable())
+                .build(),
+        );
+
+        assert_eq!(process_invoke(&CALLER, transfer, &accounts), Ok(()));
+        assert_eq!(accounts[0].lamports(), 9);
+        assert_eq!(accounts[1].lamports(), 1);
+    }
+
+    #[test]
+    fn test_instruction_data() {
+        let mut bytes = [0; 49];
+        bytes[0] = 1;
+        bytes[1..5].copy_from_slice(&2u32.to_le_bytes());
+        bytes[5..13].copy_from_slice(&3u64.to_le_bytes());
+        bytes[13..45].copy_from_slice(&[4; 32]);
+        bytes[45..].copy_from_slice(b"seed");
+
+        let mut data = InstructionData::new(&bytes, ProgramError::InvalidInstructionData);
+
+        assert_eq!(data.u8(), Ok(1));
+        assert_eq!(data.u32(), Ok(2));
+        assert_eq!(data.u64(), Ok(3));
+        assert_eq!(data.pubkey(), Ok([4; 32]));
+        assert_eq!(data.slice(5), Err(ProgramError::InvalidInstructionData));
+        assert_eq!(data.slice(4), Ok(&b"seed"[..]));
+        assert_eq!(data.u8(), Err(ProgramError::InvalidInstructionData));
+    }
+
+    #[test]
+    fn test_readonly_modified() {
+        fn modify(_: &Pubkey, accounts: &[AccountInfo], _: &[u8]) -> ProgramResult {
+            unsafe { *accounts[0].borrow_mut_lamports_unchecked() += 1 };
+            Ok(())
+        }
+
+        fn caller(_: &Pubkey, accounts: &[AccountInfo], _: &[u8]) -> ProgramResult {
+            let instruction = Instruction {
+                program_id: &CALLEE,
+                accounts: &[AccountMeta::readonly(accounts[0].key())],
+                data: &[],
+            };
+            invoke(&instruction, &[&accounts[0]])
+        }
+
+        reset();
+        register_program(CALLER, caller);
+        register_program(CALLEE, modify);
+
+        let accounts = InputAccounts::new(
+            InputBuilder::new(CALLER)
+                .account(InputAccount::new([3; 32], 10, [0; 32]).writable())
+                .build(),
+        );
+
+        assert_eq!(
+            process_instruction(&CALLER, &accounts, &[]),
+            Err(ProgramError::Immutable)
+        );
+        assert_eq!(accounts[0].lamports(), 10);
+    }
+
+    #[test]
+    fn test_failed_invoke_restores_accounts() {
+        fn fail(_: &Pubkey, accounts: &[AccountInfo], _: &[u8]) -> ProgramResult {
+            accounts[0].realloc(4, true)?;
+            accounts[0].try_borrow_mut_data()?.copy_from_slice(&[1; 4]);
+            *accounts[0].try_borrow_mut_lamports()? = 0;
+            unsafe { accounts[0].assign(&CALLEE) };
+            Err(ProgramError::Custom(0))
+        }
+
+        fn caller(_: &Pubkey, accounts: &[AccountInfo], _: &[u8]) -> ProgramResult {
+            let instruction = Instruction {
+                program_id: &CALLEE,
+                accounts: &[AccountMeta::writable(accounts[0].key())],
+                data: &[],
+            };
+            invoke(&instruction, &[&accounts[0]])
+        }
+
+        reset();
+        register_program(CALLER, caller);
+        register_program(CALLEE, fail);
+
+        let accounts = InputAccounts::new(
+            InputBuilder::new(CALLER)
+                .account(
+                    InputAccount::new([3; 32], 10, [0; 32])
+                        .data(&[2; 2])
+                        .writable(),
+                )
+                .build(),
+        );
+
+        assert_eq!(
+            process_instruction(&CALLER, &accounts, &[]),
+            Err(ProgramError::Custom(0))
+        );
+        assert_eq!(accounts[0].lamports(), 10);
+        assert!(accounts[0].is_owned_by(&[0; 32]));
+        assert_eq!(*accounts[0].try_borrow_data().unwrap(), [2; 2]);
+    }
+}